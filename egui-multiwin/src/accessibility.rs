@@ -0,0 +1,308 @@
+//! This module contains helpers for querying the accessibility settings of the operating system, and the optional accesskit integration.
+
+lazy_static::lazy_static! {
+    /// The reduced motion setting, queried once because it can involve running a command
+    static ref REDUCED_MOTION: bool = query_reduced_motion();
+}
+
+/// Returns true when the operating system has been asked to reduce motion (animations).
+/// This is a best-effort query, it returns false when the setting cannot be determined.
+/// The setting is only queried the first time this is called.
+pub fn prefers_reduced_motion() -> bool {
+    *REDUCED_MOTION
+}
+
+/// Enables or disables egui animations for the given context. The animation time in use when animations are disabled
+/// is kept in the memory of the context, and restored when they are enabled again.
+pub fn set_reduced_motion(ctx: &egui::Context, reduced: bool) {
+    let id = egui::Id::new("egui_multiwin_animation_time");
+    let saved: Option<f32> = ctx.data(|d| d.get_temp(id));
+    let mut style = (*ctx.style()).clone();
+    if reduced {
+        if saved.is_none() {
+            ctx.data_mut(|d| d.insert_temp(id, style.animation_time));
+        }
+        style.animation_time = 0.0;
+    } else if let Some(time) = saved {
+        ctx.data_mut(|d| d.remove::<f32>(id));
+        style.animation_time = time;
+    } else {
+        return;
+    }
+    ctx.set_style(style);
+}
+
+/// Query the operating system for the reduced motion setting.
+fn query_reduced_motion() -> bool {
+    #[cfg(target_os = "linux")]
+    {
+        // gnome and derivatives store the setting in gsettings
+        if let Some(s) = command_output(
+            "gsettings",
+            &["get", "org.gnome.desktop.interface", "enable-animations"],
+        ) {
+            return s.trim() == "false";
+        }
+    }
+    #[cfg(target_os = "macos")]
+    {
+        if let Some(s) = command_output(
+            "defaults",
+            &["read", "com.apple.universalaccess", "reduceMotion"],
+        ) {
+            return s.trim() == "1";
+        }
+    }
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::UI::WindowsAndMessaging::{
+            SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION,
+        };
+        // The "Show animations in Windows" setting
+        let mut animations: windows_sys::Win32::Foundation::BOOL = 1;
+        // SAFETY: SPI_GETCLIENTAREAANIMATION writes a single BOOL to the pointer
+        let ok = unsafe {
+            SystemParametersInfoW(
+                SPI_GETCLIENTAREAANIMATION,
+                0,
+                &mut animations as *mut _ as *mut core::ffi::c_void,
+                0,
+            )
+        };
+        if ok != 0 {
+            return animations == 0;
+        }
+    }
+    false
+}
+
+/// Run a command, returning the standard output when the command was successful.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn command_output(cmd: &str, args: &[&str]) -> Option<String> {
+    let output = std::process::Command::new(cmd).args(args).output().ok()?;
+    if output.status.success() {
+        String::from_utf8(output.stdout).ok()
+    } else {
+        None
+    }
+}
+
+/// Custom event types implement this to carry accesskit action requests (from screen readers and other assistive technology) to windows.
+/// Only used with the `accesskit` feature. Windows only enable accesskit when the custom event type implements this trait,
+/// applications with other event types still build, without accessibility. The custom event must be created from an action request,
+/// and its `window_id` function must return the window id of the action request, so the event is delivered to the correct window.
+#[cfg(feature = "accesskit")]
+pub trait AccessKitEvent:
+    From<egui_glow::egui_winit::accesskit_winit::ActionRequestEvent> + Send + 'static
+{
+    /// Returns the action request contained by the event, if it is one
+    fn accesskit_request(&self) -> Option<&egui_glow::egui_winit::accesskit_winit::ActionRequestEvent>;
+}
+
+/// NoEvent cannot carry action requests, so assistive technology can read the windows but action requests are dropped.
+#[cfg(feature = "accesskit")]
+impl From<egui_glow::egui_winit::accesskit_winit::ActionRequestEvent> for crate::NoEvent {
+    fn from(_: egui_glow::egui_winit::accesskit_winit::ActionRequestEvent) -> Self {
+        Self {}
+    }
+}
+
+#[cfg(feature = "accesskit")]
+impl AccessKitEvent for crate::NoEvent {
+    fn accesskit_request(&self) -> Option<&egui_glow::egui_winit::accesskit_winit::ActionRequestEvent> {
+        None
+    }
+}
+
+/// The action request is the payload of the event, sent to the window of the request.
+#[cfg(feature = "accesskit")]
+impl From<egui_glow::egui_winit::accesskit_winit::ActionRequestEvent> for crate::AnyEvent {
+    fn from(e: egui_glow::egui_winit::accesskit_winit::ActionRequestEvent) -> Self {
+        Self::for_window(crate::multi_window::WindowTarget::Window(e.window_id), e)
+    }
+}
+
+#[cfg(feature = "accesskit")]
+impl AccessKitEvent for crate::AnyEvent {
+    fn accesskit_request(&self) -> Option<&egui_glow::egui_winit::accesskit_winit::ActionRequestEvent> {
+        self.downcast_ref()
+    }
+}
+
+/// Selects the accesskit support for a custom event type E. Used by the code generated by the [`tracked_window`](macro.tracked_window.html) macro.
+/// The methods of [AccessKitSupport] are used when E implements [AccessKitEvent], otherwise the ones of [AccessKitFallback] are used.
+#[cfg(feature = "accesskit")]
+#[doc(hidden)]
+pub struct AccessKitProbe<E>(pub std::marker::PhantomData<E>);
+
+/// The accesskit support for custom event types that implement [AccessKitEvent].
+#[cfg(feature = "accesskit")]
+#[doc(hidden)]
+pub trait AccessKitSupport<E: 'static> {
+    /// Enable accesskit for the window
+    fn init(
+        &self,
+        egui: &mut egui_glow::EguiGlow,
+        window: &winit::window::Window,
+        proxy: &winit::event_loop::EventLoopProxy<E>,
+    );
+
+    /// Returns the action request contained by the event, if it is one
+    fn request<'a>(&self, event: &'a E) -> Option<&'a egui_glow::egui_winit::accesskit_winit::ActionRequestEvent>;
+}
+
+#[cfg(feature = "accesskit")]
+impl<E: AccessKitEvent> AccessKitSupport<E> for AccessKitProbe<E> {
+    fn init(
+        &self,
+        egui: &mut egui_glow::EguiGlow,
+        window: &winit::window::Window,
+        proxy: &winit::event_loop::EventLoopProxy<E>,
+    ) {
+        let ctx = egui.egui_ctx.clone();
+        egui.egui_winit.init_accesskit(window, proxy.clone(), move || {
+            ctx.enable_accesskit();
+            ctx.request_repaint();
+            ctx.accesskit_placeholder_tree_update()
+        });
+    }
+
+    fn request<'a>(&self, event: &'a E) -> Option<&'a egui_glow::egui_winit::accesskit_winit::ActionRequestEvent> {
+        event.accesskit_request()
+    }
+}
+
+/// The accesskit support for all other custom event types, accesskit stays disabled.
+#[cfg(feature = "accesskit")]
+#[doc(hidden)]
+pub trait AccessKitFallback<E: 'static> {
+    /// Does nothing, the custom event type cannot deliver action requests
+    fn init(
+        &self,
+        _egui: &mut egui_glow::EguiGlow,
+        _window: &winit::window::Window,
+        _proxy: &winit::event_loop::EventLoopProxy<E>,
+    ) {
+    }
+
+    /// Always returns None
+    fn request<'a>(&self, _event: &'a E) -> Option<&'a egui_glow::egui_winit::accesskit_winit::ActionRequestEvent> {
+        None
+    }
+}
+
+#[cfg(feature = "accesskit")]
+impl<E: 'static> AccessKitFallback<E> for &AccessKitProbe<E> {}
+
+/// Enables accesskit for a window. Used by the code generated by the [`tracked_window`](macro.tracked_window.html) macro.
+#[cfg(feature = "accesskit")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __accesskit_init {
+    ($event:ty, $egui:expr, $window:expr, $proxy:expr) => {
+        if let Some(proxy) = $proxy {
+            #[allow(unused_imports)]
+            use egui_multiwin::accessibility::{AccessKitFallback as _, AccessKitSupport as _};
+            (&egui_multiwin::accessibility::AccessKitProbe::<$event>(std::marker::PhantomData)).init($egui, $window, proxy);
+        }
+    };
+}
+
+/// Enables accesskit for a window. Used by the code generated by the [`tracked_window`](macro.tracked_window.html) macro.
+#[cfg(not(feature = "accesskit"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __accesskit_init {
+    ($event:ty, $egui:expr, $window:expr, $proxy:expr) => {
+        let _ = (&$egui, $window, $proxy);
+    };
+}
+
+/// Passes an accesskit action request contained in a custom event to egui. Used by the code generated by the [`tracked_window`](macro.tracked_window.html) macro.
+#[cfg(feature = "accesskit")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __accesskit_event {
+    ($event:ty, $egui:expr, $ue:expr) => {{
+        #[allow(unused_imports)]
+        use egui_multiwin::accessibility::{AccessKitFallback as _, AccessKitSupport as _};
+        if let Some(r) = (&egui_multiwin::accessibility::AccessKitProbe::<$event>(std::marker::PhantomData)).request($ue) {
+            $egui
+                .egui_winit
+                .on_accesskit_action_request(r.request.clone());
+        }
+    }};
+}
+
+/// Passes an accesskit action request contained in a custom event to egui. Used by the code generated by the [`tracked_window`](macro.tracked_window.html) macro.
+#[cfg(not(feature = "accesskit"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __accesskit_event {
+    ($event:ty, $egui:expr, $ue:expr) => {
+        let _ = (&$egui, $ue);
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for the reduced motion setting and for selecting the accesskit support of custom event types
+
+    use super::*;
+    #[cfg(feature = "accesskit")]
+    use egui_glow::egui_winit::accesskit_winit::ActionRequestEvent;
+
+    /// Reduced motion disables animations, and enabling them again restores the animation time the application had set
+    #[test]
+    fn reduced_motion_restores_animation_time() {
+        let ctx = egui::Context::default();
+        ctx.style_mut(|s| s.animation_time = 0.5);
+        set_reduced_motion(&ctx, true);
+        assert_eq!(ctx.style().animation_time, 0.0);
+        // Disabling twice keeps the original time
+        set_reduced_motion(&ctx, true);
+        set_reduced_motion(&ctx, false);
+        assert_eq!(ctx.style().animation_time, 0.5);
+
+        // Enabling animations that were never disabled leaves the style alone
+        ctx.style_mut(|s| s.animation_time = 0.25);
+        set_reduced_motion(&ctx, false);
+        assert_eq!(ctx.style().animation_time, 0.25);
+    }
+
+    /// A custom event type that does not implement AccessKitEvent
+    #[cfg(feature = "accesskit")]
+    struct PlainEvent;
+
+    /// Create an action request for a window
+    #[cfg(feature = "accesskit")]
+    fn action_request() -> ActionRequestEvent {
+        ActionRequestEvent {
+            window_id: winit::window::WindowId::from(1),
+            request: egui::accesskit::ActionRequest {
+                action: egui::accesskit::Action::Focus,
+                target: egui::accesskit::NodeId(1),
+                data: None,
+            },
+        }
+    }
+
+    /// Action requests reach windows through AnyEvent, NoEvent and other event types build but carry no requests
+    #[cfg(feature = "accesskit")]
+    #[test]
+    // The borrow selects the fallback when the event type does not implement AccessKitEvent
+    #[allow(clippy::needless_borrow)]
+    fn probe_selects_support() {
+        let any = crate::AnyEvent::from(action_request());
+        assert_eq!(any.window_id(), Some(winit::window::WindowId::from(1)));
+        let r = (&AccessKitProbe::<crate::AnyEvent>(std::marker::PhantomData)).request(&any);
+        assert!(r.is_some());
+
+        let none = crate::NoEvent::from(action_request());
+        let r = (&AccessKitProbe::<crate::NoEvent>(std::marker::PhantomData)).request(&none);
+        assert!(r.is_none());
+
+        let r = (&AccessKitProbe::<PlainEvent>(std::marker::PhantomData)).request(&PlainEvent);
+        assert!(r.is_none());
+    }
+}
@@ -0,0 +1,73 @@
+//! This module defines the clipboard that is given to windows.
+
+/// A clipboard that windows can copy text to and paste text from. Implement this to provide a custom clipboard backend.
+pub trait ClipboardProvider {
+    /// Get the text currently on the clipboard, if there is any
+    fn get_text(&mut self) -> Option<String>;
+    /// Put text onto the clipboard
+    fn set_text(&mut self, text: &str);
+}
+
+/// The clipboard type given to the redraw and custom_event functions of windows
+pub type Clipboard = dyn ClipboardProvider;
+
+impl ClipboardProvider for arboard::Clipboard {
+    fn get_text(&mut self) -> Option<String> {
+        arboard::Clipboard::get_text(self).ok()
+    }
+
+    fn set_text(&mut self, text: &str) {
+        let _e = arboard::Clipboard::set_text(self, text);
+    }
+}
+
+/// A clipboard that only exists inside the application. Used when the system clipboard is not available.
+#[derive(Debug, Default)]
+pub struct MemoryClipboard {
+    /// The text on the clipboard
+    text: Option<String>,
+}
+
+impl ClipboardProvider for MemoryClipboard {
+    fn get_text(&mut self) -> Option<String> {
+        self.text.clone()
+    }
+
+    fn set_text(&mut self, text: &str) {
+        self.text = Some(text.to_string());
+    }
+}
+
+/// Create the system clipboard. If the system clipboard is not available (such as on some headless or wayland systems),
+/// a warning is printed and a [MemoryClipboard] is used instead.
+pub fn system_clipboard() -> Box<dyn ClipboardProvider> {
+    match arboard::Clipboard::new() {
+        Ok(c) => Box::new(c),
+        Err(e) => {
+            eprintln!(
+                "egui-multiwin: the system clipboard is not available ({}), using an in-memory clipboard",
+                e
+            );
+            Box::new(MemoryClipboard::default())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for the clipboard
+
+    use super::*;
+
+    /// The memory clipboard starts empty and returns the latest text put on it
+    #[test]
+    fn memory_clipboard() {
+        let mut clipboard = MemoryClipboard::default();
+        assert_eq!(clipboard.get_text(), None);
+        clipboard.set_text("first");
+        clipboard.set_text("second");
+        assert_eq!(clipboard.get_text(), Some("second".to_string()));
+        // Pasting does not take the text off the clipboard
+        assert_eq!(clipboard.get_text(), Some("second".to_string()));
+    }
+}
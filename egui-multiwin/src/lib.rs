@@ -12,6 +12,7 @@
 //! The majority of the code is created by the pair of macros named [`multi_window`](macro.multi_window.html) and [`tracked_window`](macro.tracked_window.html)
 //!
 //! The main struct for this crate is defined by the [`multi_window`](macro.multi_window.html) macro.
+//! The [`window_enum`](macro.window_enum.html) macro declares the enum of all windows and invokes both macros, so all windows can be listed in one place.
 //!
 //! Generally you will create a struct for data that is common to all windows, implement the `CommonEventHandler` trait on it.
 //!
@@ -26,6 +27,13 @@
 //! allowing custom logic to send events that can create windows and modify the common state of the application as required. Create a multiwindow instance,
 //! then create window requests to make initial windows, and add them to the multiwindow with the add function. Create an instance of your common data
 //! structure, and finally call run of your multiwindow instance.
+//!
+//! Images can be shown with `ui.image("file://...")` once image loaders are installed with `MultiWindow::install_image_loaders`.
+//! This requires the `image_loaders` feature, which enables loading png and jpeg files from disk. The `image_loaders_svg` feature adds
+//! svg support. Other formats can be enabled with the features of the `image` crate, in the dependencies of the application.
+//!
+//! Native menu bars are available with the `menu` feature, see the `menu` module. System tray icons are available with the `tray` feature,
+//! see the `tray` module.
 
 #![deny(missing_docs)]
 #![deny(clippy::missing_docs_in_private_items)]
@@ -36,8 +44,20 @@ pub use {
     arboard, egui, egui_glow, enum_dispatch, glutin, raw_window_handle, raw_window_handle_5,
     thiserror, winit,
 };
+#[cfg(feature = "image_loaders")]
+pub use egui_extras;
+#[cfg(feature = "menu")]
+pub use muda;
+#[cfg(feature = "tray")]
+pub use tray_icon;
+pub mod accessibility;
+pub mod clipboard;
+#[cfg(feature = "menu")]
+pub mod menu;
 pub mod multi_window;
 pub mod tracked_window;
+#[cfg(feature = "tray")]
+pub mod tray;
 
 /// A generic non-event providing struct that users can use when they don't need custom events.
 #[derive(Debug)]
@@ -49,3 +69,81 @@ impl NoEvent {
         None
     }
 }
+
+/// A custom event type that carries a payload of any type, for applications where different windows receive unrelated events.
+/// Each window downcasts the payload to the type it expects, instead of every window sharing one enum of all events.
+/// ```ignore
+/// egui_multiwin::tracked_window!(crate::AppCommon, egui_multiwin::AnyEvent, crate::windows::MyWindows);
+/// egui_multiwin::multi_window!(crate::AppCommon, egui_multiwin::AnyEvent, crate::windows::MyWindows);
+///
+/// struct Progress(f32);
+///
+/// // Sending an event to a window, from any thread
+/// proxy.send_event(egui_multiwin::AnyEvent::for_window(egui_multiwin::multi_window::WindowTarget::Request(id), Progress(0.5)));
+///
+/// // In the window
+/// fn custom_event(&mut self, event: &egui_multiwin::AnyEvent, ctx: &mut RedrawContext) -> RedrawResponse {
+///     let mut response = RedrawResponse::default();
+///     if let Some(Progress(p)) = event.downcast_ref::<Progress>() {
+///         self.progress = *p;
+///         response.repaint = true;
+///     }
+///     response
+/// }
+/// ```
+pub struct AnyEvent {
+    /// The window the event is for, None for events handled by `CommonEventHandler::process_event`
+    target: Option<multi_window::WindowTarget>,
+    /// The payload of the event
+    payload: Box<dyn std::any::Any + Send>,
+}
+
+impl AnyEvent {
+    /// Create an event that is not for a specific window
+    pub fn new(payload: impl std::any::Any + Send) -> Self {
+        Self {
+            target: None,
+            payload: Box::new(payload),
+        }
+    }
+
+    /// Create an event for a window
+    pub fn for_window(target: multi_window::WindowTarget, payload: impl std::any::Any + Send) -> Self {
+        Self {
+            target: Some(target),
+            payload: Box::new(payload),
+        }
+    }
+
+    /// Returns the window the event is for
+    pub fn window_id(&self) -> Option<WindowId> {
+        self.target.and_then(|t| t.window_id())
+    }
+
+    /// Returns true when the payload is of type T
+    pub fn is<T: std::any::Any>(&self) -> bool {
+        self.payload.is::<T>()
+    }
+
+    /// Get the payload, if it is of type T
+    pub fn downcast_ref<T: std::any::Any>(&self) -> Option<&T> {
+        self.payload.downcast_ref()
+    }
+
+    /// Take the payload, if it is of type T. Otherwise the event is given back.
+    pub fn downcast<T: std::any::Any>(self) -> Result<T, Self> {
+        let target = self.target;
+        self.payload
+            .downcast()
+            .map(|p| *p)
+            .map_err(|payload| Self { target, payload })
+    }
+}
+
+impl std::fmt::Debug for AnyEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AnyEvent")
+            .field("target", &self.target)
+            .finish_non_exhaustive()
+    }
+}
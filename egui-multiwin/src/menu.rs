@@ -0,0 +1,60 @@
+//! This module contains the optional native menu bar integration, based on the muda crate. Only available with the `menu` feature.
+//!
+//! Build a menu with muda, attach it to a window with [attach_to_window], and forward the menu activations to the event loop
+//! as custom events with [forward_events]. The custom events are then handled like any other, by `CommonEventHandler::process_event`,
+//! or by `TrackedWindow::custom_event` when the event returns a window id.
+//! ```ignore
+//! let menu = egui_multiwin::muda::Menu::new();
+//! let file = egui_multiwin::muda::Submenu::new("File", true);
+//! let quit = egui_multiwin::muda::MenuItem::new("Quit", true, None);
+//! file.append(&quit).unwrap();
+//! menu.append(&file).unwrap();
+//! egui_multiwin::menu::forward_events(proxy, |e| CustomEvent::Menu(e.id));
+//! // Once the window is created, from window_created for example
+//! egui_multiwin::menu::attach_to_window(&menu, window).unwrap();
+//! ```
+
+use crate::multi_window::WindowError;
+
+/// Attach a menu bar to a window. On windows the menu is shown in the window, on macos the menu becomes the global menu of the
+/// application, regardless of the window given. Menus on linux require a gtk window, so this returns [WindowError::Unsupported] there.
+pub fn attach_to_window(
+    menu: &muda::Menu,
+    window: &winit::window::Window,
+) -> Result<(), WindowError> {
+    #[cfg(target_os = "windows")]
+    {
+        use raw_window_handle_5::HasRawWindowHandle;
+        if let raw_window_handle_5::RawWindowHandle::Win32(h) = window.raw_window_handle() {
+            return menu
+                .init_for_hwnd(h.hwnd as isize)
+                .map_err(|_| WindowError::Unsupported);
+        }
+        Err(WindowError::Unsupported)
+    }
+    #[cfg(target_os = "macos")]
+    {
+        let _ = window;
+        menu.init_for_nsapp();
+        Ok(())
+    }
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        let _ = (menu, window);
+        Err(WindowError::Unsupported)
+    }
+}
+
+/// Deliver every menu activation to the event loop as a custom event, created from the menu event by the given function.
+/// This replaces any previous menu event handler.
+pub fn forward_events<T: Send + 'static>(
+    proxy: winit::event_loop::EventLoopProxy<T>,
+    f: impl Fn(muda::MenuEvent) -> T + Send + Sync + 'static,
+) {
+    let proxy = std::sync::Mutex::new(proxy);
+    muda::MenuEvent::set_event_handler(Some(move |e| {
+        if let Ok(proxy) = proxy.lock() {
+            let _e = proxy.send_event(f(e));
+        }
+    }));
+}
@@ -1,6 +1,9 @@
 //! This defines the MultiWindow struct. This is the main struct used in the main function of a user application.
 
-use std::{collections::HashMap, sync::Mutex};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Mutex,
+};
 
 use winit::window::WindowId;
 
@@ -10,23 +13,30 @@ lazy_static::lazy_static! {
     pub static ref WINDOW_TABLE: Mutex<HashMap<u32, Option<WindowId>>> = Mutex::new(HashMap::new());
 }
 
-/// Creates a new id for a window request that the user program can do things with
-pub fn new_id() -> u32 {
-    let mut l = WINDOW_REQUEST_ID.lock().unwrap();
-    let mut table = WINDOW_TABLE.lock().unwrap();
+/// Creates a new id for a window request that the user program can do things with.
+/// Returns [WindowError::NoIdsLeft] if every possible id is already in use.
+pub fn new_id() -> Result<u32, WindowError> {
+    // The table only holds plain data, so it is still usable if another thread panicked while holding the lock
+    let mut l = WINDOW_REQUEST_ID
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    let mut table = WINDOW_TABLE.lock().unwrap_or_else(|e| e.into_inner());
+    if table.len() as u64 > u32::MAX as u64 {
+        return Err(WindowError::NoIdsLeft);
+    }
+    // There is a free id, so this finds one
     loop {
         *l = l.wrapping_add(1);
         if !table.contains_key(&l) {
             table.insert(*l, None);
-            break;
+            return Ok(*l);
         }
     }
-    *l
 }
 
 /// Retrieve a window id
 pub fn get_window_id(id: u32) -> Option<WindowId> {
-    let table = WINDOW_TABLE.lock().unwrap();
+    let table = WINDOW_TABLE.lock().unwrap_or_else(|e| e.into_inner());
     if let Some(id) = table.get(&id) {
         *id
     } else {
@@ -34,8 +44,338 @@ pub fn get_window_id(id: u32) -> Option<WindowId> {
     }
 }
 
+/// Decide the control flow of a window after it handled an event. A window that asked to quit, from `redraw` or `custom_event`,
+/// and a window that is not a root window while no root window exists, close by returning None. is_root is None for windows
+/// without window data, like viewports. Used by the code generated by the [`tracked_window`](macro.tracked_window.html) macro.
+pub fn window_control_flow(
+    current: Option<winit::event_loop::ControlFlow>,
+    quit: bool,
+    is_root: Option<bool>,
+    root_window_exists: bool,
+) -> Option<winit::event_loop::ControlFlow> {
+    if quit || (is_root == Some(false) && !root_window_exists) {
+        None
+    } else {
+        current
+    }
+}
+
+/// The window a custom event is for, either a winit window id or a window request id. A request id is translated when the event is delivered.
+/// Custom event types can store an `Option<WindowTarget>` and forward their `window_id` function to [WindowTarget::window_id].
+/// An event for a window that does not exist, because it has not been created yet or has already closed, is not delivered to any window.
+/// It is handled as an event for no window instead, by `CommonEventHandler::process_event`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowTarget {
+    /// The window with the given winit window id
+    Window(WindowId),
+    /// The window created by the window request with the given id
+    Request(u32),
+}
+
+impl WindowTarget {
+    /// The winit window id of the target window, None if the window does not exist
+    pub fn window_id(&self) -> Option<WindowId> {
+        match self {
+            Self::Window(id) => Some(*id),
+            Self::Request(id) => get_window_id(*id),
+        }
+    }
+}
+
+impl From<WindowId> for WindowTarget {
+    fn from(id: WindowId) -> Self {
+        Self::Window(id)
+    }
+}
+
+impl From<u32> for WindowTarget {
+    fn from(id: u32) -> Self {
+        Self::Request(id)
+    }
+}
+
+/// Decides when the application exits as windows close. Set with `MultiWindow::set_exit_policy`.
+/// When no window is marked as root, the first window added acts as the root window.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ExitPolicy {
+    /// Closing the last root window closes all other windows, and the application exits. Other root windows keep the application running. This is the default.
+    #[default]
+    WhenNoRoots,
+    /// Windows stay open when the root windows close, the application exits when the last window of any kind closes.
+    WhenNoWindows,
+    /// Closing any root window closes all windows and exits the application, like `MultiWindow::quit` does. Windows can still veto with `can_quit`.
+    WhenAnyRootCloses,
+}
+
+/// A handle for quitting the whole application, obtained from `MultiWindow::quit_handle`. It can be cloned and stored in the common data,
+/// so any window can request the application to quit from its redraw function. The request is handled after the event loop processes its current event.
+#[derive(Clone, Debug, Default)]
+pub struct QuitHandle {
+    /// Set when a quit has been requested
+    flag: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+impl QuitHandle {
+    /// Request the application to quit
+    pub fn quit(&self) {
+        self.flag.store(true, std::sync::atomic::Ordering::Relaxed);
+    }
+
+    /// Returns true if a quit has been requested and not handled yet
+    pub fn is_requested(&self) -> bool {
+        self.flag.load(std::sync::atomic::Ordering::Relaxed)
+    }
+
+    /// Clear the request, returning true if a quit was requested
+    pub fn take(&self) -> bool {
+        self.flag.swap(false, std::sync::atomic::Ordering::Relaxed)
+    }
+}
+
+/// The repaints requested with a [RepaintSignal] that have not been handled yet
+#[derive(Debug, Default)]
+pub struct RepaintRequests {
+    /// True when every window should be repainted
+    pub all: bool,
+    /// The window request ids of the windows to repaint
+    pub ids: HashSet<u32>,
+}
+
+/// The repaint requests shared between a `MultiWindow` and its [RepaintSignal]s
+#[derive(Clone, Debug, Default)]
+pub struct RepaintQueue {
+    /// The pending requests
+    requests: std::sync::Arc<Mutex<RepaintRequests>>,
+}
+
+impl RepaintQueue {
+    /// Add requests to the queue, returning true if the queue was empty before
+    fn push(&self, f: impl FnOnce(&mut RepaintRequests)) -> bool {
+        let mut r = self.requests.lock().unwrap_or_else(|e| e.into_inner());
+        let was_empty = !r.all && r.ids.is_empty();
+        f(&mut r);
+        was_empty
+    }
+
+    /// Take all pending requests, None if there are none
+    pub fn take(&self) -> Option<RepaintRequests> {
+        let mut r = self.requests.lock().unwrap_or_else(|e| e.into_inner());
+        if !r.all && r.ids.is_empty() {
+            return None;
+        }
+        Some(std::mem::take(&mut *r))
+    }
+}
+
+/// A handle for repainting windows from any thread, obtained from `MultiWindow::repaint_signal`. This is useful when background work
+/// changes what a window shows, without needing a custom event that carries any data.
+///
+/// The event loop is woken up with an event created by the wake function given to `MultiWindow::repaint_signal`. That event is delivered
+/// like any other custom event, so it should be ignored by `CommonEventHandler::process_event`. Requests made before the event loop handles
+/// the wake event are combined, so one wake event is sent for all of them.
+pub struct RepaintSignal<T: 'static> {
+    /// The queue of the requests
+    queue: RepaintQueue,
+    /// The proxy used to wake the event loop
+    proxy: winit::event_loop::EventLoopProxy<T>,
+    /// Creates the event that wakes the event loop
+    wake: std::sync::Arc<dyn Fn() -> T + Send + Sync>,
+}
+
+impl<T: 'static> Clone for RepaintSignal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            queue: self.queue.clone(),
+            proxy: self.proxy.clone(),
+            wake: self.wake.clone(),
+        }
+    }
+}
+
+impl<T: 'static> RepaintSignal<T> {
+    /// Create a signal that adds requests to the given queue, waking the event loop with an event created by wake
+    pub fn new(
+        queue: RepaintQueue,
+        proxy: winit::event_loop::EventLoopProxy<T>,
+        wake: impl Fn() -> T + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            queue,
+            proxy,
+            wake: std::sync::Arc::new(wake),
+        }
+    }
+
+    /// Request a repaint of the window with the given window request id
+    pub fn request_repaint(&self, id: u32) {
+        if self.queue.push(|r| {
+            r.ids.insert(id);
+        }) {
+            let _e = self.proxy.send_event((self.wake)());
+        }
+    }
+
+    /// Request a repaint of all windows
+    pub fn request_repaint_all(&self) {
+        if self.queue.push(|r| r.all = true) {
+            let _e = self.proxy.send_event((self.wake)());
+        }
+    }
+}
+
+/// Errors that can occur when manipulating an existing window
+#[derive(thiserror::Error, Debug)]
+pub enum WindowError {
+    /// The specified window does not exist
+    #[error("The window does not exist")]
+    NotFound,
+    /// The operation is not supported on this platform
+    #[error("The operation is not supported on this platform")]
+    Unsupported,
+    /// Every window request id is in use
+    #[error("All window request ids are in use")]
+    NoIdsLeft,
+}
+
+/// A snapshot of the input state for the entire application, aggregated across all windows.
+#[derive(Clone, Debug, Default)]
+pub struct InputSnapshot {
+    /// The modifier keys currently held
+    pub modifiers: winit::keyboard::ModifiersState,
+    /// The mouse buttons currently held
+    pub mouse_buttons: HashSet<winit::event::MouseButton>,
+    /// The window that currently has keyboard focus, if any
+    pub focused_window: Option<WindowId>,
+}
+
+impl InputSnapshot {
+    /// Update the snapshot with an event for the specified window.
+    /// When focus moves to another window (or away from the application) held modifiers and mouse buttons are cleared,
+    /// because the release events for those will not be delivered to the window that lost focus.
+    pub fn update(&mut self, window_id: WindowId, event: &winit::event::WindowEvent) {
+        match event {
+            winit::event::WindowEvent::Focused(focused) => {
+                if *focused {
+                    if self.focused_window != Some(window_id) {
+                        self.modifiers = winit::keyboard::ModifiersState::empty();
+                        self.mouse_buttons.clear();
+                    }
+                    self.focused_window = Some(window_id);
+                } else if self.focused_window == Some(window_id) {
+                    self.modifiers = winit::keyboard::ModifiersState::empty();
+                    self.mouse_buttons.clear();
+                    self.focused_window = None;
+                }
+            }
+            winit::event::WindowEvent::ModifiersChanged(m) => {
+                self.modifiers = m.state();
+            }
+            winit::event::WindowEvent::MouseInput { state, button, .. } => match state {
+                winit::event::ElementState::Pressed => {
+                    self.mouse_buttons.insert(*button);
+                }
+                winit::event::ElementState::Released => {
+                    self.mouse_buttons.remove(button);
+                }
+            },
+            winit::event::WindowEvent::Destroyed if self.focused_window == Some(window_id) => {
+                self.modifiers = winit::keyboard::ModifiersState::empty();
+                self.mouse_buttons.clear();
+                self.focused_window = None;
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The position, size, and state of a single window. Coordinates are in physical pixels.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct WindowGeometry {
+    /// The position of the outer corner of the window, if the platform supports it
+    pub position: Option<(i32, i32)>,
+    /// The size of the inside of the window
+    pub inner_size: (u32, u32),
+    /// True when the window is maximized
+    pub maximized: bool,
+    /// True when the window is fullscreen
+    pub fullscreen: bool,
+}
+
+impl WindowGeometry {
+    /// Read the current geometry of a window
+    pub fn from_window(window: &winit::window::Window) -> Self {
+        let size = window.inner_size();
+        Self {
+            position: window.outer_position().ok().map(|p| (p.x, p.y)),
+            inner_size: (size.width, size.height),
+            maximized: window.is_maximized(),
+            fullscreen: window.fullscreen().is_some(),
+        }
+    }
+
+    /// Apply the geometry to a window builder
+    pub fn apply(&self, builder: winit::window::WindowBuilder) -> winit::window::WindowBuilder {
+        let mut builder = builder
+            .with_inner_size(winit::dpi::PhysicalSize::new(
+                self.inner_size.0,
+                self.inner_size.1,
+            ))
+            .with_maximized(self.maximized);
+        if let Some((x, y)) = self.position {
+            builder = builder.with_position(winit::dpi::PhysicalPosition::new(x, y));
+        }
+        if self.fullscreen {
+            builder = builder.with_fullscreen(Some(winit::window::Fullscreen::Borderless(None)));
+        }
+        builder
+    }
+}
+
+/// The geometry of a single window in a saved layout
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutWindow {
+    /// The window request id of the window when the layout was saved. These ids are not stable between runs of the application.
+    pub id: u32,
+    /// The title of the window, useful for matching windows when restoring a layout
+    pub title: String,
+    /// The geometry of the window
+    pub geometry: WindowGeometry,
+}
+
+/// The geometry of all windows of an application, used to restore window placement on the next launch
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Layout {
+    /// The windows in the layout
+    pub windows: Vec<LayoutWindow>,
+}
+
+/// A window of a [LayoutSnapshot], identified by its role
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutSnapshotWindow {
+    /// The role of the window, see `NewWindowRequest::with_role`
+    pub role: String,
+    /// The geometry of the window
+    pub geometry: WindowGeometry,
+}
+
+/// The set of open windows of an application, used to reopen the same windows on the next launch. Only windows with a role are
+/// included, because the role is what the application uses to create the window again. The state of the windows themselves is up to
+/// the application to save.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LayoutSnapshot {
+    /// The windows in the snapshot, in the order they were opened
+    pub windows: Vec<LayoutSnapshotWindow>,
+}
+
 /// Create the dynamic tracked_window module for a egui_multiwin application. Takes three arguments. First argument is the type name of the common data structure for your application.
 /// Second argument is the type for custom events (or egui_multiwin::NoEvent if that functionality is not desired). Third argument is the enum of all windows. It needs to be enum_dispatch.
+/// The third argument can instead be `Box<dyn crate::egui_multiwin_dynamic::tracked_window::TrackedWindow>`, when window types are not all known at compile time.
+/// Windows are then created with `NewWindowRequest::new_boxed`.
 #[macro_export]
 macro_rules! tracked_window {
     ($common:ty,$event:ty, $window:ty) => {
@@ -56,15 +396,13 @@ macro_rules! tracked_window {
             use egui_multiwin::glutin::surface::SurfaceAttributesBuilder;
             use egui_multiwin::glutin::surface::WindowSurface;
             use egui_multiwin::raw_window_handle_5::{HasRawDisplayHandle, HasRawWindowHandle};
-            use egui_multiwin::tracked_window::{ContextHolder, TrackedWindowOptions};
+            use egui_multiwin::tracked_window::{ContextHolder, TrackedWindowOptions, WindowVariant};
             use egui_multiwin::winit::window::WindowId;
             use egui_multiwin::winit::{
                 event::Event,
                 event_loop::{ControlFlow, EventLoopWindowTarget},
             };
-            use egui_multiwin::{arboard, glutin, winit};
-
-            use $window;
+            use egui_multiwin::{glutin, winit};
 
             /// The return value of the redraw function of trait `TrackedWindow`
             pub struct RedrawResponse {
@@ -72,6 +410,8 @@ macro_rules! tracked_window {
                 pub quit: bool,
                 /// A list of windows that the window desires to have created.
                 pub new_windows: Vec<NewWindowRequest>,
+                /// Set by `custom_event` when the event changed what the window shows, so the window is redrawn. Ignored for `redraw`.
+                pub repaint: bool,
             }
 
             impl Default for RedrawResponse {
@@ -79,10 +419,63 @@ macro_rules! tracked_window {
                     Self {
                         quit: false,
                         new_windows: Vec::new(),
+                        repaint: false,
                     }
                 }
             }
 
+            /// Everything a window has access to while it is redrawn or handles a custom event. New capabilities are added as fields
+            /// of this struct, so they do not change the signatures of the `TrackedWindow` functions.
+            ///
+            /// Migrating from the previous signatures only requires getting the arguments from the context:
+            /// ```ignore
+            /// // Before
+            /// fn redraw(&mut self, c: &mut AppCommon, egui: &mut EguiGlow, window: &Window, clipboard: &mut Clipboard, dt: Duration) -> RedrawResponse {
+            ///     egui_multiwin::egui::CentralPanel::default().show(&egui.egui_ctx, |ui| ui.label(format!("{}", c.clicks)));
+            ///     RedrawResponse::default()
+            /// }
+            /// // After
+            /// fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+            ///     let c = &mut *ctx.common;
+            ///     egui_multiwin::egui::CentralPanel::default().show(&ctx.egui.egui_ctx, |ui| ui.label(format!("{}", c.clicks)));
+            ///     RedrawResponse::default()
+            /// }
+            /// ```
+            pub struct RedrawContext<'a> {
+                /// The data common to all windows
+                pub common: &'a mut $common,
+                /// The egui instance of the window
+                pub egui: &'a mut EguiGlow,
+                /// The winit window
+                pub window: &'a egui_multiwin::winit::window::Window,
+                /// The clipboard
+                pub clipboard: &'a mut egui_multiwin::clipboard::Clipboard,
+                /// The time elapsed since the previous redraw of the window (or since the window was created, for the first redraw)
+                pub dt: std::time::Duration,
+                /// The number of frames painted for the window so far, starting at 0
+                pub frame: u64,
+                /// The pixels per point (scale) of the window
+                pub pixels_per_point: f32,
+            }
+
+            impl<'a> RedrawContext<'a> {
+                /// The modifier keys held down. This reflects the input of the most recent frame, so it can be stored for use
+                /// outside of egui, such as in `opengl_before` and `opengl_after`.
+                pub fn modifiers(&self) -> egui::Modifiers {
+                    self.egui.egui_ctx.input(|i| i.modifiers)
+                }
+
+                /// The latest position of the pointer, in points, or None when the pointer is not over the window. This reflects the input of the most recent frame.
+                pub fn pointer_pos(&self) -> Option<egui::Pos2> {
+                    self.egui.egui_ctx.input(|i| i.pointer.latest_pos())
+                }
+
+                /// The keys held down. This reflects the input of the most recent frame.
+                pub fn pressed_keys(&self) -> std::collections::HashSet<egui::Key> {
+                    self.egui.egui_ctx.input(|i| i.keys_down.clone())
+                }
+            }
+
             /// A window being tracked by a `MultiWindow`. All tracked windows will be forwarded all events
             /// received on the `MultiWindow`'s event loop.
             #[egui_multiwin::enum_dispatch::enum_dispatch]
@@ -98,33 +491,87 @@ macro_rules! tracked_window {
                     true
                 }
 
+                /// Called when the user asks to close the window, such as with the close button of the window. Returns true to close the window, which is the default.
+                /// Return false to keep the window open, for example to show a "Save changes?" dialog. The window can then close itself by returning quit from redraw,
+                /// or the application can close it with `MultiWindow::confirm_close`.
+                fn on_close_requested(&mut self, _c: &mut $common) -> bool {
+                    true
+                }
+
+                /// Called for every window event of the window, after the window has handled resizing, closing, and redrawing, but before egui receives the event.
+                /// Returns true to consume the event so egui does not process it. Defaults to false, passing every event to egui.
+                fn raw_window_event(&mut self, _c: &mut $common, _event: &egui_multiwin::winit::event::WindowEvent) -> bool {
+                    false
+                }
+
+                /// Called before every frame with the input egui is about to receive, so it can be changed. For example, setting a fixed time
+                /// gives deterministic animations in tests. Does nothing by default.
+                fn modify_input(&mut self, _input: &mut egui_multiwin::egui::RawInput) {}
+
+                /// Called when a file is dropped onto the window. egui also receives the event, so the file is still available in the dropped_files of the egui input. Does nothing by default.
+                fn file_dropped(&mut self, _c: &mut $common, _path: std::path::PathBuf) {}
+
+                /// Called when a file is dragged over the window. egui also receives the event. Does nothing by default.
+                fn file_hovered(&mut self, _c: &mut $common, _path: std::path::PathBuf) {}
+
                 /// Sets whether or not the window is a root window. Does nothing by default
                 fn set_root(&mut self, _root: bool) {}
 
-                /// Handles a custom event sent specifically to this window.
+                /// Called once after the window has been created, its context made current, and egui initialized.
+                /// This is a good place for one-time setup that needs the actual window. Does nothing by default.
+                fn window_created(&mut self, _window: &egui_multiwin::winit::window::Window) {}
+
+                /// Handles a custom event sent specifically to this window. The dt of the context is the time since the previous redraw.
+                /// Set `repaint` in the response when the event changed what the window shows, otherwise the window is not redrawn for it.
                 fn custom_event(
                     &mut self,
                     _event: &$event,
-                    _c: &mut $common,
-                    _egui: &mut EguiGlow,
-                    _window: &egui_multiwin::winit::window::Window,
-                    _clipboard: &mut egui_multiwin::arboard::Clipboard,
+                    _ctx: &mut RedrawContext,
                 ) -> RedrawResponse {
                     RedrawResponse {
                         quit: false,
                         new_windows: vec![],
+                        repaint: false,
                     }
                 }
 
-                /// Runs the redraw for the window. See RedrawResponse for the return value.
-                fn redraw(
-                    &mut self,
-                    c: &mut $common,
-                    egui: &mut EguiGlow,
-                    window: &egui_multiwin::winit::window::Window,
-                    clipboard: &mut egui_multiwin::arboard::Clipboard,
-                ) -> RedrawResponse;
-                /// Allows opengl rendering to be done underneath all of the egui stuff of the window
+                /// Runs the redraw for the window. See RedrawResponse for the return value, and RedrawContext for what is available to the window.
+                fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse;
+                /// Allows overriding the pixels per point (scale) of the window, for example to implement zoom. Defaults to None, using the scale of the operating system.
+                /// The value is applied at the start of every frame.
+                fn pixels_per_point(&self, _window: &egui_multiwin::winit::window::Window) -> Option<f32> {
+                    None
+                }
+
+                /// Returns the style group of the window. Windows in the same group share the egui style (including visuals),
+                /// a style change made in one window is applied to the others at the start of their next frame. Defaults to None, for a style of its own.
+                fn share_style_group(&self) -> Option<egui_multiwin::tracked_window::GroupId> {
+                    None
+                }
+
+                /// Returns true when the window is animating and should be redrawn every frame, without calling request_repaint from redraw.
+                /// Use the dt of the RedrawContext to advance animations. Defaults to false, redrawing only when egui requests it.
+                /// The max_fps option of the window still limits how often it is redrawn.
+                fn continuous(&self) -> bool {
+                    false
+                }
+
+                /// Returns true to let the user zoom the window with Ctrl+scroll, Ctrl+Plus, Ctrl+Minus, and Ctrl+0 to reset, like a web browser.
+                /// The zoom factor of the window is kept for as long as the window exists. Defaults to false.
+                fn allow_zoom(&self) -> bool {
+                    false
+                }
+
+                /// Returns true when the window should be cleared automatically before each frame. Defaults to true.
+                /// When false, no clearing is done at all and opengl_before is responsible for the entire contents of the framebuffer.
+                /// egui is drawn with alpha blending, so anything left over from a previous frame shows through transparent areas.
+                fn auto_clear(&self) -> bool {
+                    true
+                }
+
+                /// Allows opengl rendering to be done underneath all of the egui stuff of the window.
+                /// When the render_target option of the window is set, its render target is bound while this runs.
+                /// The window is given so the rendering can match the size of the framebuffer, with `window.inner_size()`.
                 /// # Safety
                 ///
                 /// opengl functions are unsafe. This function would require calling opengl functions.
@@ -132,6 +579,7 @@ macro_rules! tracked_window {
                     &mut self,
                     _c: &mut $common,
                     _gl: &Arc<egui_multiwin::egui_glow::painter::Context>,
+                    _window: &egui_multiwin::winit::window::Window,
                 ) {
                 }
                 /// Allows opengl rendering to be done on top of all of the egui stuff of the window
@@ -142,7 +590,94 @@ macro_rules! tracked_window {
                     &mut self,
                     _c: &mut $common,
                     _gl: &Arc<egui_multiwin::egui_glow::painter::Context>,
+                    _window: &egui_multiwin::winit::window::Window,
+                ) {
+                }
+            }
+
+            /// Allows a `Box<dyn TrackedWindow>` to be used as the window type instead of an enum_dispatch enum, for applications that
+            /// do not know all window types at compile time. Every call is forwarded to the boxed window.
+            impl<T: TrackedWindow + ?Sized> TrackedWindow for Box<T> {
+                fn is_root(&self) -> bool {
+                    (**self).is_root()
+                }
+
+                fn can_quit(&mut self, c: &mut $common) -> bool {
+                    (**self).can_quit(c)
+                }
+
+                fn on_close_requested(&mut self, c: &mut $common) -> bool {
+                    (**self).on_close_requested(c)
+                }
+
+                fn raw_window_event(&mut self, c: &mut $common, event: &egui_multiwin::winit::event::WindowEvent) -> bool {
+                    (**self).raw_window_event(c, event)
+                }
+
+                fn modify_input(&mut self, input: &mut egui_multiwin::egui::RawInput) {
+                    (**self).modify_input(input)
+                }
+
+                fn file_dropped(&mut self, c: &mut $common, path: std::path::PathBuf) {
+                    (**self).file_dropped(c, path)
+                }
+
+                fn file_hovered(&mut self, c: &mut $common, path: std::path::PathBuf) {
+                    (**self).file_hovered(c, path)
+                }
+
+                fn set_root(&mut self, root: bool) {
+                    (**self).set_root(root)
+                }
+
+                fn window_created(&mut self, window: &egui_multiwin::winit::window::Window) {
+                    (**self).window_created(window)
+                }
+
+                fn custom_event(&mut self, event: &$event, ctx: &mut RedrawContext) -> RedrawResponse {
+                    (**self).custom_event(event, ctx)
+                }
+
+                fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+                    (**self).redraw(ctx)
+                }
+
+                fn pixels_per_point(&self, window: &egui_multiwin::winit::window::Window) -> Option<f32> {
+                    (**self).pixels_per_point(window)
+                }
+
+                fn share_style_group(&self) -> Option<egui_multiwin::tracked_window::GroupId> {
+                    (**self).share_style_group()
+                }
+
+                fn continuous(&self) -> bool {
+                    (**self).continuous()
+                }
+
+                fn allow_zoom(&self) -> bool {
+                    (**self).allow_zoom()
+                }
+
+                fn auto_clear(&self) -> bool {
+                    (**self).auto_clear()
+                }
+
+                unsafe fn opengl_before(
+                    &mut self,
+                    c: &mut $common,
+                    gl: &Arc<egui_multiwin::egui_glow::painter::Context>,
+                    window: &egui_multiwin::winit::window::Window,
+                ) {
+                    (**self).opengl_before(c, gl, window)
+                }
+
+                unsafe fn opengl_after(
+                    &mut self,
+                    c: &mut $common,
+                    gl: &Arc<egui_multiwin::egui_glow::painter::Context>,
+                    window: &egui_multiwin::winit::window::Window,
                 ) {
+                    (**self).opengl_after(c, gl, window)
                 }
             }
 
@@ -184,6 +719,34 @@ macro_rules! tracked_window {
                 viewportid: &'a ViewportId,
                 /// The optional callback for the window
                 viewport_callback: &'a Option<Arc<DeferredViewportUiCallback>>,
+                /// The widget to focus once the window is shown and focused
+                initial_focus: &'a mut Option<egui::Id>,
+                /// The time of the previous redraw
+                last_frame: &'a mut std::time::Instant,
+                /// The number of frames painted so far
+                frame: &'a mut u64,
+                /// The color to clear the window with
+                clear_color: egui::Rgba,
+                /// The offscreen render target of the window
+                render_target: &'a mut Option<egui_multiwin::tracked_window::GlRenderTarget>,
+                /// Set once the first frame of the window has been painted
+                first_frame_done: &'a mut bool,
+                /// The latest size the surface needs to be resized to
+                pending_resize: &'a mut Option<egui_multiwin::winit::dpi::PhysicalSize<u32>>,
+                /// True when a modal dialog of the window is open, egui does not receive input events while set
+                input_blocked: bool,
+                /// True when power saving is enabled, see `MultiWindow::set_power_saving`
+                power_saving: bool,
+                /// When egui wants the window to be repainted next, None when the window is idle
+                repaint_at: &'a mut Option<std::time::Instant>,
+                /// The zoom factor of the window
+                zoom_factor: &'a mut f32,
+                /// True while the window is fully covered by other windows
+                occluded: &'a mut bool,
+                /// True when the next painted frame should be captured
+                capture_requested: &'a mut bool,
+                /// The most recently captured frame
+                capture: &'a mut Option<egui::ColorImage>,
             }
 
             impl<'a> TrackedWindowContainerInstance<'a> {
@@ -199,23 +762,106 @@ macro_rules! tracked_window {
                     gl_window: &mut egui_multiwin::tracked_window::ContextHolder<
                         PossiblyCurrentContext,
                     >,
-                    clipboard: &mut egui_multiwin::arboard::Clipboard,
+                    clipboard: &mut egui_multiwin::clipboard::Clipboard,
                 ) -> TrackedWindowControl {
                     // Child window's requested control flow.
                     let mut viewportset = self.viewportset.lock().unwrap();
 
                     let mut redraw = || {
-                        let input = self.egui.egui_winit.take_egui_input(&gl_window.window);
-                        let ppp = self.egui.egui_ctx.pixels_per_point();
+                        if let Some(ppp) = self.window.window_data().and_then(|w| w.pixels_per_point(&gl_window.window)) {
+                            self.egui.egui_ctx.set_pixels_per_point(ppp);
+                        }
+                        let style_group = self.window.window_data().and_then(|w| w.share_style_group());
+                        if let Some(group) = style_group {
+                            let style = STYLE_GROUPS.with(|g| g.borrow().get(&group).cloned());
+                            if let Some(style) = style {
+                                if *style != *self.egui.egui_ctx.style() {
+                                    self.egui.egui_ctx.set_style(style);
+                                }
+                            }
+                        }
+                        // Zooming with the keyboard is done by egui at the end of the frame
+                        let allow_zoom = self.window.window_data().map(|w| w.allow_zoom());
+                        if let Some(allow_zoom) = allow_zoom {
+                            self.egui.egui_ctx.options_mut(|o| o.zoom_with_keyboard = allow_zoom);
+                        }
+                        let mut input = self.egui.egui_winit.take_egui_input(&gl_window.window);
+                        if let Some(window) = self.window.window_data() {
+                            window.modify_input(&mut input);
+                        }
+                        let apply_focus = input.focused && gl_window.window.is_visible() != Some(false);
                         self.egui.egui_ctx.begin_frame(input);
+                        if apply_focus {
+                            if let Some(id) = self.initial_focus.take() {
+                                self.egui.egui_ctx.memory_mut(|m| m.request_focus(id));
+                            }
+                        }
+                        let now = std::time::Instant::now();
+                        let dt = now.duration_since(*self.last_frame);
+                        *self.last_frame = now;
                         let mut rr = RedrawResponse::default();
                         if let Some(cb) = self.viewport_callback {
                             cb(&self.egui.egui_ctx);
                         }
                         else if let Some(window) = self.window.window_data() {
-                            rr = window.redraw(c, self.egui, &gl_window.window, clipboard);
+                            let pixels_per_point = self.egui.egui_ctx.pixels_per_point();
+                            let mut ctx = RedrawContext {
+                                common: &mut *c,
+                                egui: &mut *self.egui,
+                                window: &gl_window.window,
+                                clipboard: &mut *clipboard,
+                                dt,
+                                frame: *self.frame,
+                                pixels_per_point,
+                            };
+                            rr = window.redraw(&mut ctx);
+                        }
+                        *self.frame += 1;
+                        if allow_zoom == Some(true) {
+                            // egui_winit turns Ctrl+scroll into zoom events
+                            let ctx = &self.egui.egui_ctx;
+                            let delta = ctx.input(|i| i.zoom_delta());
+                            if delta != 1.0 {
+                                ctx.set_zoom_factor((ctx.zoom_factor() * delta).clamp(0.2, 5.0));
+                            }
+                        }
+                        if self.input_blocked {
+                            // Dim the window while a modal dialog is open
+                            let ctx = &self.egui.egui_ctx;
+                            ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("egui_multiwin_modal_dim")))
+                                .rect_filled(ctx.screen_rect(), 0.0, egui::Color32::from_black_alpha(96));
+                        }
+                        let mut full_output = self.egui.egui_ctx.end_frame();
+                        *self.zoom_factor = self.egui.egui_ctx.zoom_factor();
+
+                        // Copied text is handled here, egui_winit takes care of the cursor icon, ime, and opening urls
+                        let platform_output = std::mem::take(&mut full_output.platform_output);
+                        if !platform_output.copied_text.is_empty() {
+                            clipboard.set_text(&platform_output.copied_text);
+                        }
+                        self.egui.egui_winit.handle_platform_output(&gl_window.window, platform_output);
+
+                        if let Some(group) = style_group {
+                            let style = self.egui.egui_ctx.style();
+                            STYLE_GROUPS.with(|g| {
+                                let mut g = g.borrow_mut();
+                                if g.get(&group).map(|s| **s != *style).unwrap_or(true) {
+                                    g.insert(group, style);
+                                }
+                            });
+                        }
+
+                        if update_immediate_viewports(
+                            gl_window.window.id(),
+                            &self.egui.egui_ctx,
+                            self.viewportset,
+                            &full_output.viewport_output,
+                            el,
+                        ) || IMMEDIATE_VIEWPORTS.with(|m| !m.borrow().is_empty())
+                        {
+                            // Rendering immediate viewports switches opengl contexts
+                            let _e = gl_window.make_current();
                         }
-                        let full_output = self.egui.egui_ctx.end_frame();
 
                         if self.viewport_callback.is_none() {
                             let mut remove_id = Vec::new();
@@ -235,21 +881,26 @@ macro_rules! tracked_window {
                         }
 
                         for (viewport_id, viewport_output) in &full_output.viewport_output {
-                            if viewport_id != &egui::viewport::ViewportId::ROOT && !viewportset.contains(viewport_id) {
+                            if viewport_id != &egui::viewport::ViewportId::ROOT
+                                && !matches!(viewport_output.class, egui::viewport::ViewportClass::Immediate)
+                                && !viewportset.contains(viewport_id)
+                            {
+                                // Without a free id the viewport is not created, it is tried again on the next frame
+                                let id = match egui_multiwin::multi_window::new_id() {
+                                    Ok(id) => id,
+                                    Err(_) => continue,
+                                };
                                 let builder =
                                     egui_multiwin::egui_glow::egui_winit::create_winit_window_builder(
                                         &self.egui.egui_ctx,
                                         el,
                                         viewport_output.builder.to_owned(),
                                     );
-                                let options = TrackedWindowOptions {
-                                    shader: None,
-                                    vsync: false,
-                                };
+                                let options = TrackedWindowOptions::default();
                                 let vp = NewWindowRequest::new_viewport(
                                     builder,
                                     options,
-                                    egui_multiwin::multi_window::new_id(),
+                                    id,
                                     viewport_output.builder.clone(),
                                     viewport_id.to_owned(),
                                     self.viewportset.to_owned(),
@@ -257,6 +908,13 @@ macro_rules! tracked_window {
                                 );
                                 viewportset.insert(viewport_id.to_owned());
                                 rr.new_windows.push(vp);
+                            } else if viewport_id != &egui::viewport::ViewportId::ROOT
+                                && matches!(viewport_output.class, egui::viewport::ViewportClass::Deferred)
+                            {
+                                // The builder can change while the viewport is shown, such as its title
+                                VIEWPORT_BUILDERS.with(|b| {
+                                    b.borrow_mut().insert(*viewport_id, viewport_output.builder.clone())
+                                });
                             }
                         }
 
@@ -264,39 +922,108 @@ macro_rules! tracked_window {
                             .viewport_output
                             .get(self.viewportid);
                         let repaint_after = vp_output.map(|v| v.repaint_delay).unwrap_or(std::time::Duration::from_millis(1000));
+                        let continuous = self.window.window_data().map(|w| w.continuous()).unwrap_or(false);
+                        let repaint_after = if continuous {
+                            std::time::Duration::ZERO
+                        } else {
+                            repaint_after
+                        };
+                        // Throttle to the maximum frame rate of the window, if there is one
+                        let repaint_after = match gl_window.options().max_fps.filter(|f| *f > 0.0) {
+                            Some(fps) => {
+                                let min_interval = std::time::Duration::from_secs_f32(1.0 / fps);
+                                repaint_after.max(min_interval.saturating_sub(self.last_frame.elapsed()))
+                            }
+                            None => repaint_after,
+                        };
 
+                        *self.repaint_at = None;
                         if rr.quit {
                             gl_window.control_flow = None;
                         } else if repaint_after.is_zero() {
                             gl_window.window.request_redraw();
-                            gl_window.control_flow = Some(egui_multiwin::winit::event_loop::ControlFlow::Poll);
+                            // The redraw request wakes the event loop by itself, so an animating window does not need to poll
+                            gl_window.control_flow = Some(if self.power_saving {
+                                egui_multiwin::winit::event_loop::ControlFlow::Wait
+                            } else {
+                                egui_multiwin::winit::event_loop::ControlFlow::Poll
+                            });
                         } else if repaint_after.as_millis() > 0 && repaint_after.as_millis() < 10000 {
+                            let when = std::time::Instant::now() + repaint_after;
+                            *self.repaint_at = Some(when);
                             gl_window.control_flow =
-                                Some(egui_multiwin::winit::event_loop::ControlFlow::WaitUntil(
-                                    std::time::Instant::now() + repaint_after,
-                                ));
+                                Some(egui_multiwin::winit::event_loop::ControlFlow::WaitUntil(when));
                         } else {
                             gl_window.control_flow = Some(egui_multiwin::winit::event_loop::ControlFlow::Wait);
                         };
+                        if *self.occluded && !rr.quit {
+                            // Nothing can be seen, so wait until the window is uncovered
+                            *self.repaint_at = None;
+                            gl_window.control_flow = Some(egui_multiwin::winit::event_loop::ControlFlow::Wait);
+                        }
 
-                        {
-                            let color = egui_multiwin::egui::Rgba::from_white_alpha(0.0);
-                            unsafe {
-                                use glow::HasContext as _;
-                                self.egui.painter
-                                    .gl()
-                                    .clear_color(color[0], color[1], color[2], color[3]);
-                                self.egui.painter.gl().clear(glow::COLOR_BUFFER_BIT);
+                        let size = gl_window.window.inner_size();
+                        let minimized = gl_window.window.is_minimized() == Some(true)
+                            || *self.occluded
+                            || size.width == 0
+                            || size.height == 0;
+                        if minimized {
+                            // Nothing is visible, so skip painting. Texture changes must still be applied, or they would be lost.
+                            for (id, delta) in &full_output.textures_delta.set {
+                                self.egui.painter.set_texture(*id, delta);
+                            }
+                            for id in &full_output.textures_delta.free {
+                                self.egui.painter.free_texture(*id);
+                            }
+                        } else {
+                            if let Some(size) = self.pending_resize.take() {
+                                gl_window.resize(size);
+                            }
+                            let color = self.clear_color;
+                            let auto_clear = self.window.window_data().map(|w| w.auto_clear()).unwrap_or(true);
+                            if gl_window.options().render_target {
+                                let gl = self.egui.painter.gl();
+                                let size = (size.width, size.height);
+                                if let Some(target) = self.render_target.as_mut() {
+                                    unsafe { target.resize(gl, size) };
+                                } else {
+                                    let depth = gl_window.options().depth_bits.is_some();
+                                    *self.render_target =
+                                        unsafe { egui_multiwin::tracked_window::GlRenderTarget::new(gl, size, depth) }.ok();
+                                }
+                                if let Some(target) = self.render_target.as_ref() {
+                                    unsafe { target.bind(gl) };
+                                }
+                            }
+                            if auto_clear {
+                                unsafe {
+                                    use glow::HasContext as _;
+                                    self.egui.painter
+                                        .gl()
+                                        .clear_color(color[0], color[1], color[2], color[3]);
+                                    if gl_window.options().depth_bits.is_some() {
+                                        self.egui.painter
+                                            .gl()
+                                            .clear(glow::COLOR_BUFFER_BIT | glow::DEPTH_BUFFER_BIT);
+                                    } else {
+                                        self.egui.painter.gl().clear(glow::COLOR_BUFFER_BIT);
+                                    }
+                                }
                             }
 
                             // draw things behind egui here
                             if let Some(window) = self.window.window_data() {
-                                unsafe { window.opengl_before(c, self.egui.painter.gl()) };
+                                unsafe { window.opengl_before(c, self.egui.painter.gl(), &gl_window.window) };
+                            }
+                            if let Some(target) = self.render_target.as_ref() {
+                                unsafe { target.blit_to_window(self.egui.painter.gl()) };
                             }
 
+                            // Use the scale of this frame for both tessellation and painting, so a change in scale takes effect immediately
+                            let ppp = full_output.pixels_per_point;
                             let prim = self.egui
                                 .egui_ctx
-                                .tessellate(full_output.shapes, self.egui.egui_ctx.pixels_per_point());
+                                .tessellate(full_output.shapes, ppp);
                             self.egui.painter.paint_and_update_textures(
                                 gl_window.window.inner_size().into(),
                                 ppp,
@@ -306,18 +1033,60 @@ macro_rules! tracked_window {
 
                             // draw things on top of egui here
                             if let Some(window) = self.window.window_data() {
-                                unsafe { window.opengl_after(c, self.egui.painter.gl()) };
+                                unsafe { window.opengl_after(c, self.egui.painter.gl(), &gl_window.window) };
                             }
 
-                            gl_window.swap_buffers().unwrap();
+                            // The back buffer holds the finished frame until it is swapped
+                            if *self.capture_requested {
+                                *self.capture_requested = false;
+                                *self.capture = Some(unsafe {
+                                    egui_multiwin::tracked_window::read_back_buffer(
+                                        self.egui.painter.gl(),
+                                        (size.width, size.height),
+                                    )
+                                });
+                            }
+
+                            // A window created hidden is only shown once its first frame has been presented. swap_buffers skips hidden
+                            // windows, so the first frame is presented without waiting for vsync instead.
+                            let show = !*self.first_frame_done && gl_window.options().visible_after_first_frame;
+                            let swapped = if show {
+                                gl_window.swap_buffers_hidden()
+                            } else {
+                                gl_window.swap_buffers()
+                            };
+                            if swapped.is_ok() {
+                                *self.first_frame_done = true;
+                                if show {
+                                    gl_window.window.set_visible(true);
+                                    gl_window.window.request_redraw();
+                                }
+                            } else if gl_window.context_lost {
+                                // A lost context is detected here, and recreated the next time the window handles an event
+                                gl_window.window.request_redraw();
+                            }
                         }
                         rr
                     };
 
                     let response = match event {
                         egui_multiwin::winit::event::Event::UserEvent(ue) => {
+                            egui_multiwin::__accesskit_event!($event, self.egui, ue);
                             if let Some(window) = self.window.window_data() {
-                                Some(window.custom_event(ue, c, self.egui, &gl_window.window, clipboard))
+                                let mut ctx = RedrawContext {
+                                    pixels_per_point: self.egui.egui_ctx.pixels_per_point(),
+                                    common: &mut *c,
+                                    egui: &mut *self.egui,
+                                    window: &gl_window.window,
+                                    clipboard: &mut *clipboard,
+                                    dt: self.last_frame.elapsed(),
+                                    frame: *self.frame,
+                                };
+                                let rr = window.custom_event(ue, &mut ctx);
+                                if rr.repaint {
+                                    gl_window.window.request_redraw();
+                                }
+                                Some(rr)
                             }
                             else {
                                 None
@@ -327,8 +1096,15 @@ macro_rules! tracked_window {
                         egui_multiwin::winit::event::Event::NewEvents(sc) => {
                             match sc {
                                 egui_multiwin::winit::event::StartCause::ResumeTimeReached{..} => {
-                                    gl_window.window.request_redraw();
-                                    gl_window.control_flow = Some(ControlFlow::Poll); //?
+                                    if !self.power_saving {
+                                        gl_window.window.request_redraw();
+                                        gl_window.control_flow = Some(ControlFlow::Poll); //?
+                                    } else if self.repaint_at.map(|t| t <= std::time::Instant::now()).unwrap_or(false) {
+                                        // Only the windows that asked for a repaint by now are redrawn, the others keep sleeping
+                                        *self.repaint_at = None;
+                                        gl_window.window.request_redraw();
+                                        gl_window.control_flow = Some(ControlFlow::Wait);
+                                    }
                                 }
                                 egui_multiwin::winit::event::StartCause::WaitCancelled{start, requested_resume} => {
                                     if let Some(resume) = requested_resume {
@@ -348,21 +1124,77 @@ macro_rules! tracked_window {
                         egui_multiwin::winit::event::Event::WindowEvent { event, window_id } => {
                             let mut redraw_thing = None;
                             match event {
+                                egui_multiwin::winit::event::WindowEvent::Focused(false) => {
+                                    // A grabbed cursor would stay trapped in the window while the user works elsewhere
+                                    egui_multiwin::tracked_window::release_cursor(&gl_window.window);
+                                    // The input method is only enabled for the focused window. egui enables it again, and positions
+                                    // the candidate window at the text cursor, when the window is focused with a text edit active.
+                                    gl_window.window.set_ime_allowed(false);
+                                    self.egui.egui_winit.set_allow_ime(false);
+                                }
+                                egui_multiwin::winit::event::WindowEvent::Focused(true) => {
+                                    // Redraw so the input method state of a focused text edit is applied right away
+                                    gl_window.window.request_redraw();
+                                }
                                 egui_multiwin::winit::event::WindowEvent::Resized(physical_size) => {
-                                    gl_window.resize(*physical_size);
+                                    // Only the latest size matters, the surface is resized before the next paint
+                                    *self.pending_resize = Some(*physical_size);
+                                    // Painting is skipped while minimized, so repaint promptly when the window is restored
+                                    if physical_size.width > 0 && physical_size.height > 0 {
+                                        gl_window.window.request_redraw();
+                                    }
+                                }
+                                egui_multiwin::winit::event::WindowEvent::Occluded(occluded) => {
+                                    *self.occluded = *occluded;
+                                    if !*occluded {
+                                        gl_window.window.request_redraw();
+                                    }
+                                }
+                                egui_multiwin::winit::event::WindowEvent::ScaleFactorChanged { .. } => {
+                                    // winit does not provide the new size with this event, the size is read from the window instead.
+                                    // egui picks up the new scale itself when it receives the event.
+                                    *self.pending_resize = Some(gl_window.window.inner_size());
+                                    gl_window.window.request_redraw();
                                 }
                                 egui_multiwin::winit::event::WindowEvent::CloseRequested => {
-                                    gl_window.control_flow = None;
+                                    let close = self
+                                        .window
+                                        .window_data()
+                                        .map(|w| w.on_close_requested(c))
+                                        .unwrap_or(true);
+                                    if close {
+                                        gl_window.control_flow = None;
+                                    } else {
+                                        gl_window.window.request_redraw();
+                                    }
                                 }
                                 egui_multiwin::winit::event::WindowEvent::RedrawRequested => {
                                     redraw_thing = Some(redraw());
                                 }
+                                egui_multiwin::winit::event::WindowEvent::DroppedFile(path) => {
+                                    if let Some(window) = self.window.window_data() {
+                                        window.file_dropped(c, path.to_owned());
+                                    }
+                                }
+                                egui_multiwin::winit::event::WindowEvent::HoveredFile(path) => {
+                                    if let Some(window) = self.window.window_data() {
+                                        window.file_hovered(c, path.to_owned());
+                                    }
+                                }
                                 _ => {}
                             }
 
+                            let consumed = self
+                                .window
+                                .window_data()
+                                .map(|w| w.raw_window_event(c, event))
+                                .unwrap_or(false);
+
                             match event {
                                 egui_multiwin::winit::event::WindowEvent::RedrawRequested => {
                                 }
+                                _ if consumed => {}
+                                _ if self.input_blocked && egui_multiwin::tracked_window::is_input_event(event) => {}
                                 _ => {
                                     let resp = self.egui.on_window_event(&gl_window.window, event);
                                     if resp.repaint {
@@ -374,6 +1206,9 @@ macro_rules! tracked_window {
                             redraw_thing
                         }
                         egui_multiwin::winit::event::Event::LoopExiting => {
+                            if let Some(target) = self.render_target.take() {
+                                unsafe { target.destroy(self.egui.painter.gl()) };
+                            }
                             self.egui.destroy();
                             None
                         }
@@ -381,11 +1216,13 @@ macro_rules! tracked_window {
                         _ => None,
                     };
 
-                    if let Some(window) = self.window.window_data() {
-                        if !root_window_exists && !window.is_root() {
-                            gl_window.control_flow = None;
-                        }
-                    }
+                    // A quit request is honored the same way regardless of which handler produced it
+                    gl_window.control_flow = egui_multiwin::multi_window::window_control_flow(
+                        gl_window.control_flow,
+                        response.as_ref().map(|rr| rr.quit).unwrap_or(false),
+                        self.window.window_data().map(|w| w.is_root()),
+                        root_window_exists,
+                    );
 
                     TrackedWindowControl {
                         requested_control_flow: gl_window.control_flow,
@@ -422,6 +1259,74 @@ macro_rules! tracked_window {
                 pub vb: Option<ViewportBuilder>,
                 /// The viewport callback
                 viewportcb: Option<std::sync::Arc<DeferredViewportUiCallback>>,
+                /// The widget to focus on the first frame where the window is visible and focused
+                pub initial_focus: Option<egui::Id>,
+                /// The time of the previous redraw, used to calculate the frame delta time
+                last_frame: std::time::Instant,
+                /// The number of frames painted for the window
+                frame: u64,
+                /// The color used to clear the window before each frame
+                pub clear_color: egui::Rgba,
+                /// The offscreen render target, created on the first frame when the render_target option is set
+                render_target: Option<egui_multiwin::tracked_window::GlRenderTarget>,
+                /// Set once the first frame of the window has been painted
+                first_frame_done: bool,
+                /// The latest size the surface needs to be resized to. Resize events are coalesced, the surface is resized once before painting.
+                pending_resize: Option<egui_multiwin::winit::dpi::PhysicalSize<u32>>,
+                /// The parent window when this window is a modal dialog
+                pub modal_for: Option<WindowId>,
+                /// True while a modal dialog of this window is open
+                input_blocked: bool,
+                /// True when power saving is enabled for the window
+                power_saving: bool,
+                /// When egui wants the window to be repainted next
+                repaint_at: Option<std::time::Instant>,
+                /// The zoom factor of the window, kept here so it survives the egui instance being recreated
+                zoom_factor: f32,
+                /// True while the window is fully covered by other windows, painting is skipped while set
+                occluded: bool,
+                /// True when the next painted frame should be captured
+                capture_requested: bool,
+                /// The most recently captured frame, until it is taken
+                capture: Option<egui::ColorImage>,
+                /// The parent window, this window is closed when the parent closes
+                pub parent: Option<WindowId>,
+                /// The window request id the window was created with
+                request_id: u32,
+                /// The application defined role of the window
+                pub role: Option<String>,
+            }
+
+            impl CommonWindowData {
+                /// Returns true while a modal dialog of the window is open
+                pub fn input_blocked(&self) -> bool {
+                    self.input_blocked
+                }
+
+                /// Block or unblock input to the window, done while dispatching events according to the open modal dialogs
+                pub(super) fn set_input_blocked(&mut self, blocked: bool) {
+                    self.input_blocked = blocked;
+                }
+
+                /// The window request id the window was created with
+                pub fn request_id(&self) -> u32 {
+                    self.request_id
+                }
+
+                /// Record the window request id the window was created with
+                pub(super) fn set_request_id(&mut self, id: u32) {
+                    self.request_id = id;
+                }
+
+                /// Returns true when power saving is enabled for the window
+                pub fn power_saving(&self) -> bool {
+                    self.power_saving
+                }
+
+                /// Enable or disable power saving for the window, see `MultiWindow::set_power_saving`
+                pub(super) fn set_power_saving(&mut self, enabled: bool) {
+                    self.power_saving = enabled;
+                }
             }
 
             /// The container for a viewport window
@@ -455,8 +1360,25 @@ macro_rules! tracked_window {
                     }
                 }
 
+                /// Get the window data as a specific window type. This only returns Some when the window enum contains a window of type W.
+                /// The window enum needs to implement [egui_multiwin::tracked_window::WindowVariant], see the [egui_multiwin::window_variants] macro.
+                pub fn get_window_data_as<W>(&self) -> Option<&W>
+                where
+                    $window: egui_multiwin::tracked_window::WindowVariant<W>,
+                {
+                    self.get_window_data().and_then(|w| w.as_variant())
+                }
+
+                /// Get the window data, mutably, as a specific window type. This only returns Some when the window enum contains a window of type W.
+                pub fn get_window_data_as_mut<W>(&mut self) -> Option<&mut W>
+                where
+                    $window: egui_multiwin::tracked_window::WindowVariant<W>,
+                {
+                    self.get_window_data_mut().and_then(|w| w.as_variant_mut())
+                }
+
                 /// Get the common data for the window
-                fn common(&self) -> &CommonWindowData {
+                pub(super) fn common(&self) -> &CommonWindowData {
                     match self {
                         Self::PlainWindow(w) => &w.common,
                         Self::Viewport(w) => &w.common,
@@ -464,7 +1386,7 @@ macro_rules! tracked_window {
                 }
 
                 /// Get the common data, mutably, for the window
-                fn common_mut(&mut self) -> &mut CommonWindowData {
+                pub(super) fn common_mut(&mut self) -> &mut CommonWindowData {
                     match self {
                         Self::PlainWindow(w) => &mut w.common,
                         Self::Viewport(w) => &mut w.common,
@@ -487,11 +1409,172 @@ macro_rules! tracked_window {
                     }
                 }
 
+                /// Removes the viewport of a closing viewport window from the viewport set, so the parent window will create it again if it is still shown.
+                /// Returns the id of the viewport, or None for windows that are not viewports.
+                pub(super) fn close_viewport(&self) -> Option<ViewportId> {
+                    match self {
+                        Self::PlainWindow(_) => None,
+                        Self::Viewport(w) => {
+                            w.common.viewportset.lock().unwrap().remove(&w.common.viewportid);
+                            Some(w.common.viewportid)
+                        }
+                    }
+                }
+
+                /// Get the winit window for the container, if it currently exists
+                pub fn window(&self) -> Option<&winit::window::Window> {
+                    match self.gl_window() {
+                        IndeterminateWindowedContext::PossiblyCurrent(w) => Some(w.window()),
+                        IndeterminateWindowedContext::NotCurrent(w) => Some(w.window()),
+                        IndeterminateWindowedContext::Suspended(w, _) => Some(w),
+                        IndeterminateWindowedContext::None => None,
+                    }
+                }
+
+                /// Apply the changes of the latest builder of a deferred viewport to its window, such as a new title or size.
+                /// Changes that need a new window, like transparency, are not applied. Does nothing for windows that are not viewports.
+                pub fn update_viewport_builder(&mut self, builder: ViewportBuilder) {
+                    let common = self.common_mut();
+                    if let (Some(vb), IndeterminateWindowedContext::PossiblyCurrent(gl_window), Some(egui)) =
+                        (&mut common.vb, &common.gl_window, &common.egui)
+                    {
+                        let (commands, _recreate) = vb.patch(builder);
+                        if !commands.is_empty() {
+                            let mut info = egui::ViewportInfo::default();
+                            let mut screenshot_requested = false;
+                            egui_multiwin::egui_glow::egui_winit::process_viewport_commands(
+                                &egui.egui_ctx,
+                                &mut info,
+                                commands,
+                                gl_window.window(),
+                                gl_window.window().has_focus(),
+                                &mut screenshot_requested,
+                            );
+                        }
+                    }
+                }
+
+                /// Make the opengl context of this window current and run the closure with its egui context.
+                /// Returns None if egui has not been initialized for the window yet, which happens when the window handles its first event.
+                pub fn with_egui_ctx<R>(&self, f: impl FnOnce(&egui::Context) -> R) -> Option<R> {
+                    let common = self.common();
+                    if let (IndeterminateWindowedContext::PossiblyCurrent(gl_window), Some(egui)) =
+                        (&common.gl_window, &common.egui)
+                    {
+                        let _e = gl_window.make_current();
+                        Some(f(&egui.egui_ctx))
+                    } else {
+                        None
+                    }
+                }
+
+                /// Replace the window data with a different window, keeping the native window, its opengl context, and its egui instance.
+                /// `window_created` is called for the new window data. Returns the previous window data, or gives back the new window data
+                /// as an error for viewport windows, which have no window data.
+                pub fn replace_window_data(&mut self, new: $window) -> Result<$window, $window> {
+                    match self {
+                        Self::PlainWindow(w) => {
+                            let old = std::mem::replace(&mut w.window, new);
+                            if let Some(window) = w.common.gl_window.window_opt() {
+                                w.window.window_created(window);
+                                window.request_redraw();
+                            }
+                            Ok(old)
+                        }
+                        Self::Viewport(_) => Err(new),
+                    }
+                }
+
+                /// Capture the next frame painted for the window, including everything drawn by `opengl_before` and `opengl_after`.
+                /// A redraw is requested, the frame is read before it is presented. Get the captured frame with [Self::take_capture].
+                pub fn request_capture(&mut self) {
+                    self.common_mut().capture_requested = true;
+                    if let Some(window) = self.window() {
+                        window.request_redraw();
+                    }
+                }
+
+                /// Take the frame captured after [Self::request_capture]. Returns None if no frame has been captured since the last call.
+                pub fn take_capture(&mut self) -> Option<egui::ColorImage> {
+                    self.common_mut().capture.take()
+                }
+
+                /// Run the ui of an immediate viewport on the egui instance of this window and paint it.
+                /// The opengl context of this window is made current, the previously current context must be made current again afterwards.
+                fn render_immediate(&mut self, ui: Box<dyn FnOnce(&egui::Context) + '_>) {
+                    let common = self.common_mut();
+                    let clear_color = common.clear_color;
+                    if let (IndeterminateWindowedContext::PossiblyCurrent(gl_window), Some(egui)) =
+                        (&mut common.gl_window, &mut common.egui)
+                    {
+                        let _e = gl_window.make_current();
+                        let input = egui.egui_winit.take_egui_input(gl_window.window());
+                        egui.egui_ctx.begin_frame(input);
+                        ui(&egui.egui_ctx);
+                        let mut full_output = egui.egui_ctx.end_frame();
+                        egui.egui_winit.handle_platform_output(
+                            gl_window.window(),
+                            std::mem::take(&mut full_output.platform_output),
+                        );
+                        unsafe {
+                            use glow::HasContext as _;
+                            let c = clear_color;
+                            egui.painter.gl().clear_color(c[0], c[1], c[2], c[3]);
+                            egui.painter.gl().clear(glow::COLOR_BUFFER_BIT);
+                        }
+                        let ppp = full_output.pixels_per_point;
+                        let prim = egui.egui_ctx.tessellate(full_output.shapes, ppp);
+                        egui.painter.paint_and_update_textures(
+                            gl_window.window().inner_size().into(),
+                            ppp,
+                            &prim[..],
+                            &full_output.textures_delta,
+                        );
+                        let _e = gl_window.swap_buffers();
+                    }
+                }
+
+                /// Returns true if the window is minimized. None if the platform can't tell, or the window does not exist.
+                pub fn is_minimized(&self) -> Option<bool> {
+                    self.window().and_then(|w| w.is_minimized())
+                }
+
+                /// Returns true if the window is maximized. None if the window does not exist.
+                pub fn is_maximized(&self) -> Option<bool> {
+                    self.window().map(|w| w.is_maximized())
+                }
+
+                /// Returns true if the window is visible. None if the platform can't tell, or the window does not exist.
+                pub fn is_visible(&self) -> Option<bool> {
+                    self.window().and_then(|w| w.is_visible())
+                }
+
+                /// Get the raw window handle of the window, for integrating with other graphics apis.
+                /// The handle is only valid while the window exists, it must not be used after the window is closed.
+                pub fn raw_window_handle(&self) -> Option<egui_multiwin::raw_window_handle_5::RawWindowHandle> {
+                    self.window().map(|w| w.raw_window_handle())
+                }
+
+                /// Get the raw display handle of the window, for integrating with other graphics apis.
+                /// The handle is only valid while the window exists, it must not be used after the window is closed.
+                pub fn raw_display_handle(&self) -> Option<egui_multiwin::raw_window_handle_5::RawDisplayHandle> {
+                    self.window().map(|w| w.raw_display_handle())
+                }
+
+                /// The id of the egui viewport shown by the container, None for windows that are not viewports
+                pub fn viewport_id(&self) -> Option<ViewportId> {
+                    match self {
+                        Self::PlainWindow(_) => None,
+                        Self::Viewport(w) => Some(w.common.viewportid),
+                    }
+                }
+
                 /// Retrieve the window id for the container
                 pub fn get_window_id(&self) -> Option<WindowId> {
                     match self.gl_window() {
                         IndeterminateWindowedContext::PossiblyCurrent(w) => Some(w.window.id()),
                         IndeterminateWindowedContext::NotCurrent(w) => Some(w.window.id()),
+                        IndeterminateWindowedContext::Suspended(w, _) => Some(w.id()),
                         IndeterminateWindowedContext::None => {
                             None
                         }
@@ -499,6 +1582,7 @@ macro_rules! tracked_window {
                 }
 
                 /// Create a new window.
+                #[allow(clippy::too_many_arguments)]
                 pub fn create<TE>(
                     window: Option<$window>,
                     viewportset: Arc<Mutex<ViewportIdSet>>,
@@ -509,73 +1593,265 @@ macro_rules! tracked_window {
                     options: &TrackedWindowOptions,
                     vb: Option<ViewportBuilder>
                 ) -> Result<TrackedWindowContainer, DisplayCreationError> {
-                    let rdh = event_loop.raw_display_handle();
-                    let winitwindow = window_builder.build(event_loop).unwrap();
+                    let mut window_builder = options.apply(window_builder);
+                    if options.visible_after_first_frame {
+                        window_builder = window_builder.with_visible(false);
+                    }
+                    // A window made transparent by the builder needs a transparent opengl config as well
+                    let options = &TrackedWindowOptions {
+                        transparent: window_builder.window_attributes().transparent,
+                        ..*options
+                    };
+                    let winitwindow = window_builder
+                        .build(event_loop)
+                        .map_err(DisplayCreationError::WindowCreation)?;
+                    // There is no builder option for this, it can only be set on the window
+                    if !options.cursor_hittest {
+                        let _e = winitwindow.set_cursor_hittest(false);
+                    }
+                    let holder = Self::create_context(winitwindow, options).map_err(|(_, e)| e)?;
+                    let wcommon = CommonWindowData {
+                        viewportid: viewportid.to_owned(),
+                        viewportset: viewportset.clone(),
+                        gl_window: IndeterminateWindowedContext::NotCurrent(holder),
+                        vb,
+                        viewportcb,
+                        egui: None,
+                        shader: options.shader,
+                        initial_focus: None,
+                        last_frame: std::time::Instant::now(),
+                        frame: 0,
+                        clear_color: options.clear_color,
+                        render_target: None,
+                        first_frame_done: false,
+                        pending_resize: None,
+                        modal_for: None,
+                        input_blocked: false,
+                        power_saving: false,
+                        repaint_at: None,
+                        zoom_factor: 1.0,
+                        occluded: false,
+                        capture_requested: false,
+                        capture: None,
+                        parent: None,
+                        request_id: 0,
+                        role: None,
+                    };
+                    if let Some(window) = window {
+                        let w = PlainWindowContainer {
+                            window,
+                            common: wcommon,
+                        };
+                        Ok(TrackedWindowContainer::PlainWindow(w))
+                    }
+                    else {
+                        let w = ViewportWindowContainer {
+                            common: wcommon,
+                        };
+                        Ok(TrackedWindowContainer::Viewport(w))
+                    }
+                }
+
+                /// Create the opengl display, surface, and context for an existing window. This is used for new windows,
+                /// and to recreate the context of a window after the context is lost. The window is given back on failure.
+                fn create_context(
+                    winitwindow: winit::window::Window,
+                    options: &TrackedWindowOptions,
+                ) -> Result<ContextHolder<NotCurrentContext>, (Box<winit::window::Window>, DisplayCreationError)> {
+                    let mut result = Self::create_window_context(&winitwindow, options, None);
+                    // With the software-fallback feature, configs without hardware acceleration are tried when no accelerated config works
+                    if result.is_err() && egui_multiwin::tracked_window::software_fallback() {
+                        if let Ok(r) = Self::create_window_context(&winitwindow, options, Some(false)) {
+                            result = Ok(r);
+                        }
+                    }
+                    match result {
+                        Ok((context, ws, display, srgb_capable, share_group)) => Ok(
+                            egui_multiwin::tracked_window::ContextHolder::new(context, winitwindow, ws, display, *options)
+                                .with_srgb_capable(srgb_capable)
+                                .with_share_group(share_group),
+                        ),
+                        Err(e) => Err((Box::new(winitwindow), e)),
+                    }
+                }
+
+                /// Create a new context for a window that already exists and make it current, after the context was lost or
+                /// when the application is resumed. The window and its options are given back on failure.
+                fn recreate_context(
+                    winitwindow: winit::window::Window,
+                    options: &TrackedWindowOptions,
+                ) -> Result<ContextHolder<PossiblyCurrentContext>, (Box<winit::window::Window>, TrackedWindowOptions)> {
+                    Self::create_context(winitwindow, options)
+                        .map_err(|(w, _)| w)
+                        .and_then(|w| w.try_make_current().map_err(|(w, _)| w))
+                        .map_err(|w| (w, *options))
+                }
+
+                /// Create the opengl display, surface, and context for a window. Returns the context, the surface, the display,
+                /// whether the config of the surface supports sRGB, and the share group of the context.
+                /// Hardware accelerated configs are preferred or avoided according to hardware.
+                fn create_window_context(
+                    winitwindow: &winit::window::Window,
+                    options: &TrackedWindowOptions,
+                    hardware: Option<bool>,
+                ) -> Result<
+                    (
+                        NotCurrentContext,
+                        glutin::surface::Surface<WindowSurface>,
+                        glutin::display::Display,
+                        bool,
+                        Option<std::rc::Rc<egui_multiwin::tracked_window::ShareGroup>>,
+                    ),
+                    DisplayCreationError,
+                > {
+                    let rdh = winitwindow.raw_display_handle();
                     let rwh = winitwindow.raw_window_handle();
                     #[cfg(target_os = "windows")]
-                    let pref = glutin::display::DisplayApiPreference::Wgl(Some(rwh));
+                    let default_pref = glutin::display::DisplayApiPreference::Wgl(Some(rwh));
                     #[cfg(target_os = "linux")]
-                    let pref = egui_multiwin::glutin::display::DisplayApiPreference::Egl;
+                    let default_pref = egui_multiwin::glutin::display::DisplayApiPreference::Egl;
                     #[cfg(target_os = "macos")]
-                    let pref = glutin::display::DisplayApiPreference::Cgl;
-                    let display = unsafe { glutin::display::Display::new(rdh, pref) };
-                    if let Ok(display) = display {
-                        let configt = glutin::config::ConfigTemplateBuilder::default().build();
+                    let default_pref = glutin::display::DisplayApiPreference::Cgl;
+                    let pref = match options.gl_api_preference {
+                        Some(api) => api
+                            .display_api(rwh)
+                            .ok_or(DisplayCreationError::UnsupportedApi(api))?,
+                        None => default_pref,
+                    };
+                    // Windows in a share group use the display the share group was created on
+                    let group_key = options.share_group.map(|g| (g, rdh));
+                    let existing_group = group_key.and_then(|k| {
+                        SHARE_GROUPS.with(|g| g.borrow().get(&k).and_then(std::rc::Weak::upgrade))
+                    });
+                    let display = match &existing_group {
+                        Some(group) => group.display.clone(),
+                        None => unsafe { glutin::display::Display::new(rdh, pref) }
+                            .map_err(DisplayCreationError::NoDisplay)?,
+                    };
+                    let mut requested = glutin::config::ConfigTemplateBuilder::default()
+                        .prefer_hardware_accelerated(hardware);
+                    if let Some(depth) = options.depth_bits {
+                        requested = requested.with_depth_size(depth);
+                    }
+                    if options.transparent {
+                        requested = requested.with_transparency(true);
+                    }
+                    // Some drivers cannot create a surface for any config matching the requested template, so retry with a relaxed template before giving up.
+                    // The depth buffer is only relaxed when the window allows it.
+                    let relaxed_depth = options.depth_bits.filter(|_| !options.allow_depth_fallback);
+                    let mut relaxed = glutin::config::ConfigTemplateBuilder::default()
+                        .prefer_hardware_accelerated(hardware)
+                        .with_alpha_size(0)
+                        .with_transparency(false);
+                    if let Some(depth) = relaxed_depth {
+                        relaxed = relaxed.with_depth_size(depth);
+                    }
+                    let templates = [
+                        (
+                            requested.build(),
+                            format!(
+                                "alpha 8 bits, depth {} bits, transparency {}",
+                                options.depth_bits.unwrap_or(0),
+                                options.transparent
+                            ),
+                        ),
+                        (
+                            relaxed.build(),
+                            format!("no alpha, depth {} bits, no transparency", relaxed_depth.unwrap_or(0)),
+                        ),
+                    ];
+                    let mut attempted = Vec::new();
+                    let mut config_error = None;
+                    let mut found_configs = false;
+                    for (configt, description) in templates {
+                        let fallback = !attempted.is_empty();
+                        attempted.push(description);
                         let mut configs: Vec<glutin::config::Config> =
-                            unsafe { display.find_configs(configt) }.unwrap().collect();
-                        configs.sort_by(|a, b| a.num_samples().cmp(&b.num_samples()));
+                            match unsafe { display.find_configs(configt) } {
+                                Ok(c) => {
+                                    found_configs = true;
+                                    c.collect()
+                                }
+                                Err(e) => {
+                                    config_error = Some(e);
+                                    continue;
+                                }
+                            };
+                        // Prefer configs that actually have the requested depth buffer, then sRGB support when requested, then the fewest samples
+                        let depth = options.depth_bits.unwrap_or(0);
+                        configs.sort_by_key(|c| {
+                            (c.depth_size() < depth, options.srgb && !c.srgb_capable(), c.num_samples())
+                        });
                         // Try all configurations until one works
                         for config in configs {
+                            if config.depth_size() < depth && !options.allow_depth_fallback {
+                                continue;
+                            }
                             let sab: SurfaceAttributesBuilder<WindowSurface> =
                                 egui_multiwin::glutin::surface::SurfaceAttributesBuilder::default();
                             let sa = sab.build(
                                 rwh,
-                                std::num::NonZeroU32::new(winitwindow.inner_size().width).unwrap(),
-                                std::num::NonZeroU32::new(winitwindow.inner_size().height).unwrap(),
+                                std::num::NonZeroU32::new(winitwindow.inner_size().width.max(1)).unwrap(),
+                                std::num::NonZeroU32::new(winitwindow.inner_size().height.max(1)).unwrap(),
                             );
                             let ws = unsafe { display.create_window_surface(&config, &sa) };
                             if let Ok(ws) = ws {
-                                let attr =
-                                    egui_multiwin::glutin::context::ContextAttributesBuilder::new()
-                                        .build(Some(rwh));
-
-                                let gl_window =
-                                    unsafe { display.create_context(&config, &attr) }.unwrap();
-
-                                let wcommon = CommonWindowData {
-                                    viewportid: viewportid.to_owned(),
-                                    viewportset: viewportset.clone(),
-                                    gl_window: IndeterminateWindowedContext::NotCurrent(
-                                        egui_multiwin::tracked_window::ContextHolder::new(
-                                            gl_window,
-                                            winitwindow,
-                                            ws,
-                                            display,
-                                            *options,
-                                        ),
-                                    ),
-                                    vb,
-                                    viewportcb,
-                                    egui: None,
-                                    shader: options.shader,
-                                };
-                                if let Some(window) = window {
-                                    let w = PlainWindowContainer {
-                                        window,
-                                        common: wcommon,
-                                    };
-                                    return Ok(TrackedWindowContainer::PlainWindow(w));
+                                let mut attr_builder = egui_multiwin::glutin::context::ContextAttributesBuilder::new();
+                                if let Some(api) = options.context_api {
+                                    attr_builder = attr_builder.with_context_api(api.context_api());
                                 }
-                                else {
-                                    let w = ViewportWindowContainer {
-                                        common: wcommon,
+                                let attr = attr_builder.clone().build(Some(rwh));
+
+                                let (gl_window, share_group) = if let Some(key) = group_key {
+                                    let group = match &existing_group {
+                                        Some(group) => group.clone(),
+                                        None => {
+                                            let context = unsafe { display.create_context(&config, &attr) }
+                                                .map_err(DisplayCreationError::NoContext)?;
+                                            let group = std::rc::Rc::new(egui_multiwin::tracked_window::ShareGroup {
+                                                display: display.clone(),
+                                                context,
+                                            });
+                                            SHARE_GROUPS.with(|g| {
+                                                let mut g = g.borrow_mut();
+                                                // Forget the share groups whose windows have all closed
+                                                g.retain(|_, group| group.strong_count() > 0);
+                                                g.insert(key, std::rc::Rc::downgrade(&group));
+                                            });
+                                            group
+                                        }
                                     };
-                                    return Ok(TrackedWindowContainer::Viewport(w));
+                                    let attr = attr_builder
+                                        .with_sharing(&group.context)
+                                        .build(Some(rwh));
+                                    let context = unsafe { display.create_context(&config, &attr) }
+                                        .map_err(DisplayCreationError::NoContext)?;
+                                    (context, Some(group))
+                                } else {
+                                    let context = unsafe { display.create_context(&config, &attr) }
+                                        .map_err(DisplayCreationError::NoContext)?;
+                                    (context, None)
+                                };
+
+                                if fallback || config.depth_size() < depth {
+                                    eprintln!(
+                                        "egui-multiwin: no opengl config matches the requested template ({}), using one with alpha {} bits, depth {} bits, transparency {}",
+                                        attempted[0],
+                                        config.alpha_size(),
+                                        config.depth_size(),
+                                        config.supports_transparency().unwrap_or(false)
+                                    );
                                 }
+                                return Ok((gl_window, ws, display, config.srgb_capable(), share_group));
                             }
                         }
                     }
-                    panic!("No window created");
+                    if !found_configs {
+                        if let Some(e) = config_error {
+                            return Err(DisplayCreationError::NoConfigs(e));
+                        }
+                    }
+                    Err(DisplayCreationError::NoGlConfig(attempted.join("; ")))
                 }
 
                 /// Returns true if the specified event is for this window. A UserEvent (one generated by the EventLoopProxy) is not for any window.
@@ -633,6 +1909,20 @@ macro_rules! tracked_window {
                                     viewportset: &w.common.viewportset,
                                     viewportid: &w.common.viewportid,
                                     viewport_callback: &w.common.viewportcb,
+                                    initial_focus: &mut w.common.initial_focus,
+                                    last_frame: &mut w.common.last_frame,
+                                    frame: &mut w.common.frame,
+                                    clear_color: w.common.clear_color,
+                                    render_target: &mut w.common.render_target,
+                                    first_frame_done: &mut w.common.first_frame_done,
+                                    pending_resize: &mut w.common.pending_resize,
+                                    input_blocked: w.common.input_blocked,
+                                    power_saving: w.common.power_saving,
+                                    repaint_at: &mut w.common.repaint_at,
+                                    zoom_factor: &mut w.common.zoom_factor,
+                                    occluded: &mut w.common.occluded,
+                                    capture_requested: &mut w.common.capture_requested,
+                                    capture: &mut w.common.capture,
                                 })
                             }
                             else {
@@ -647,6 +1937,20 @@ macro_rules! tracked_window {
                                     viewportset: &w.common.viewportset,
                                     viewportid: &w.common.viewportid,
                                     viewport_callback: &w.common.viewportcb,
+                                    initial_focus: &mut w.common.initial_focus,
+                                    last_frame: &mut w.common.last_frame,
+                                    frame: &mut w.common.frame,
+                                    clear_color: w.common.clear_color,
+                                    render_target: &mut w.common.render_target,
+                                    first_frame_done: &mut w.common.first_frame_done,
+                                    pending_resize: &mut w.common.pending_resize,
+                                    input_blocked: w.common.input_blocked,
+                                    power_saving: w.common.power_saving,
+                                    repaint_at: &mut w.common.repaint_at,
+                                    zoom_factor: &mut w.common.zoom_factor,
+                                    occluded: &mut w.common.occluded,
+                                    capture_requested: &mut w.common.capture_requested,
+                                    capture: &mut w.common.capture,
                                 })
                             }
                             else {
@@ -663,24 +1967,73 @@ macro_rules! tracked_window {
                     event: &winit::event::Event<$event>,
                     el: &EventLoopWindowTarget<$event>,
                     root_window_exists: bool,
-                    fontmap: &HashMap<String, egui::FontData>,
-                    clipboard: &mut arboard::Clipboard,
+                    settings: &EguiSettings<'_>,
+                    clipboard: &mut egui_multiwin::clipboard::Clipboard,
                 ) -> TrackedWindowControl {
+                    if let winit::event::Event::Suspended = event {
+                        self.suspend();
+                        return TrackedWindowControl {
+                            requested_control_flow: Some(ControlFlow::Wait),
+                            windows_to_create: Vec::new(),
+                        };
+                    }
+
                     // Activate this gl_window so we can use it.
                     // We cannot activate it without full ownership, so temporarily move the gl_window into the current scope.
                     // It *must* be returned at the end.
                     let gl_window =
                         mem::replace(self.gl_window_mut(), IndeterminateWindowedContext::None);
-                    let mut gl_window = match gl_window {
+                    let mut recreated = false;
+                    let gl_window = match gl_window {
                         IndeterminateWindowedContext::PossiblyCurrent(w) => {
-                            let _e = w.make_current();
-                            w
+                            if w.is_context_lost() {
+                                // Recreate the context for the existing window, the egui instance is recreated below
+                                let options = *w.options();
+                                forget_share_group(w.window(), &options);
+                                recreated = true;
+                                self.common_mut().egui = None;
+                                // The objects of the render target were lost with the context
+                                self.common_mut().render_target = None;
+                                Self::recreate_context(w.into_window(), &options)
+                            } else {
+                                Ok(w)
+                            }
+                        }
+                        IndeterminateWindowedContext::NotCurrent(w) => {
+                            let options = *w.options();
+                            w.try_make_current().map_err(|(w, _)| (w, options))
+                        }
+                        IndeterminateWindowedContext::Suspended(w, options) => {
+                            if let winit::event::Event::Resumed = event {
+                                // Create a new surface and context, the egui instance is recreated below
+                                recreated = true;
+                                Self::recreate_context(w, &options)
+                            } else {
+                                // Nothing can be drawn until the application is resumed
+                                *self.gl_window_mut() = IndeterminateWindowedContext::Suspended(w, options);
+                                return TrackedWindowControl {
+                                    requested_control_flow: Some(ControlFlow::Wait),
+                                    windows_to_create: Vec::new(),
+                                };
+                            }
                         }
-                        IndeterminateWindowedContext::NotCurrent(w) => w.make_current().unwrap(),
                         IndeterminateWindowedContext::None => {
                             panic!("there's no window context???")
                         }
                     };
+                    let mut gl_window = match gl_window {
+                        Ok(w) => w,
+                        Err((window, options)) => {
+                            // Without a usable opengl context the window cannot continue, so close it. The window is kept suspended
+                            // in case it does not close, and another context is created when the application is resumed.
+                            *self.gl_window_mut() = IndeterminateWindowedContext::Suspended(*window, options);
+                            self.try_quit(c);
+                            return TrackedWindowControl {
+                                requested_control_flow: None,
+                                windows_to_create: Vec::new(),
+                            };
+                        }
+                    };
 
                     // Now that the window is active, create a context if it is missing.
                     match self.common().egui.as_ref() {
@@ -693,21 +2046,30 @@ macro_rules! tracked_window {
 
                             unsafe {
                                 use glow::HasContext as _;
-                                gl.enable(glow::FRAMEBUFFER_SRGB);
+                                // sRGB framebuffers are not part of OpenGL ES
+                                if gl_window.srgb() && !gl.version().is_embedded {
+                                    gl.enable(glow::FRAMEBUFFER_SRGB);
+                                }
                             }
 
                             let egui = egui_glow::EguiGlow::new(el, gl, self.common().shader, None);
-                            {
-                                let mut fonts = egui::FontDefinitions::default();
-                                for (name, font) in fontmap {
-                                    fonts.font_data.insert(name.clone(), font.clone());
-                                    fonts.families.insert(
-                                        egui::FontFamily::Name(name.to_owned().into()),
-                                        vec![name.to_owned()],
-                                    );
-                                }
-                                egui.egui_ctx.set_fonts(fonts)
+                            egui.egui_ctx.set_fonts(settings.fonts.clone());
+                            if settings.reduced_motion {
+                                set_reduced_motion(&egui.egui_ctx, true);
+                            }
+                            if settings.image_loaders {
+                                egui_multiwin::tracked_window::install_image_loaders(&egui.egui_ctx);
+                            }
+                            if let Some(visuals) = settings.visuals {
+                                egui.egui_ctx.set_visuals(visuals.clone());
+                            }
+                            if settings.debug {
+                                set_debug(&egui.egui_ctx, true);
+                            }
+                            if let Some(options) = settings.tessellation_options {
+                                egui.egui_ctx.tessellation_options_mut(|o| *o = *options);
                             }
+                            egui.egui_ctx.set_zoom_factor(self.common().zoom_factor);
                             if let Some(vb) = &self.common().vb {
                                 egui_multiwin::egui_glow::egui_winit::apply_viewport_builder_to_window(
                                     &egui.egui_ctx,
@@ -716,7 +2078,16 @@ macro_rules! tracked_window {
                                 );
                             }
                             egui.egui_ctx.set_embed_viewports(false);
+                            egui::Context::set_immediate_viewport_renderer(render_immediate_viewport);
                             self.common_mut().egui = Some(egui);
+                            if let Some(egui) = &mut self.common_mut().egui {
+                                egui_multiwin::__accesskit_init!($event, egui, gl_window.window(), settings.proxy);
+                            }
+                            if !recreated {
+                                if let Some(window) = self.get_window_data_mut() {
+                                    window.window_created(gl_window.window());
+                                }
+                            }
                         }
                         Some(_) => (),
                     };
@@ -751,11 +2122,49 @@ macro_rules! tracked_window {
                     result
                 }
 
+                /// Destroy the egui instance, the opengl context, and the surface of the window, keeping the window itself.
+                /// They are created again when the application is resumed.
+                fn suspend(&mut self) {
+                    self.destroy();
+                    let gl_window = mem::replace(self.gl_window_mut(), IndeterminateWindowedContext::None);
+                    *self.gl_window_mut() = match gl_window {
+                        IndeterminateWindowedContext::PossiblyCurrent(w) => {
+                            let options = *w.options();
+                            forget_share_group(w.window(), &options);
+                            IndeterminateWindowedContext::Suspended(w.into_window(), options)
+                        }
+                        IndeterminateWindowedContext::NotCurrent(w) => {
+                            let options = *w.options();
+                            forget_share_group(w.window(), &options);
+                            IndeterminateWindowedContext::Suspended(w.into_window(), options)
+                        }
+                        other => other,
+                    };
+                }
+
+                /// Destroy the egui instance of the window, in preparation for dropping the window
+                pub(super) fn destroy(&mut self) {
+                    let common = self.common_mut();
+                    if let (IndeterminateWindowedContext::PossiblyCurrent(gl_window), Some(egui)) =
+                        (&common.gl_window, &mut common.egui)
+                    {
+                        let _e = gl_window.make_current();
+                        if let Some(target) = common.render_target.take() {
+                            unsafe { target.destroy(egui.painter.gl()) };
+                        }
+                        egui.destroy();
+                    }
+                    common.egui = None;
+                }
+
                 fn try_quit(&mut self, c: &mut $common) {
                     match self {
                         Self::PlainWindow(w) => {
                             if w.window.can_quit(c) {
                                 if let Some(egui) = &mut w.common.egui {
+                                    if let Some(target) = w.common.render_target.take() {
+                                        unsafe { target.destroy(egui.painter.gl()) };
+                                    }
                                     egui.destroy();
                                 }
                             }
@@ -767,12 +2176,185 @@ macro_rules! tracked_window {
                 }
             }
 
+            /// Enables or disables egui animations for the given context, see [egui_multiwin::accessibility::set_reduced_motion]
+            pub fn set_reduced_motion(ctx: &egui::Context, reduced: bool) {
+                egui_multiwin::accessibility::set_reduced_motion(ctx, reduced);
+            }
+
+            /// Enables or disables the debug painting of egui for the given context. While enabled, hovering a widget shows where it
+            /// was created, and the space widgets expand into is outlined.
+            pub fn set_debug(ctx: &egui::Context, on: bool) {
+                let mut style = (*ctx.style()).clone();
+                style.debug.debug_on_hover = on;
+                style.debug.show_expand_width = on;
+                style.debug.show_expand_height = on;
+                ctx.set_style(style);
+            }
+
+            thread_local! {
+                /// The current style of each style group
+                static STYLE_GROUPS: std::cell::RefCell<HashMap<egui_multiwin::tracked_window::GroupId, Arc<egui::Style>>> =
+                    std::cell::RefCell::new(HashMap::new());
+                /// The windows for immediate viewports, rendered by [render_immediate_viewport] during the frame of their parent window
+                static IMMEDIATE_VIEWPORTS: std::cell::RefCell<HashMap<ViewportId, ImmediateViewportWindow>> =
+                    std::cell::RefCell::new(HashMap::new());
+                /// The opengl share groups, by group and display. The windows of a group hold the share group, it is destroyed when the
+                /// last of them closes. A group is removed when its context is lost or the application is suspended, so later windows start a new one.
+                static SHARE_GROUPS: std::cell::RefCell<HashMap<
+                    (egui_multiwin::tracked_window::GroupId, egui_multiwin::raw_window_handle_5::RawDisplayHandle),
+                    std::rc::Weak<egui_multiwin::tracked_window::ShareGroup>,
+                >> = std::cell::RefCell::new(HashMap::new());
+                /// The latest builders of deferred viewports, given by their parent windows, that have not been applied yet
+                static VIEWPORT_BUILDERS: std::cell::RefCell<HashMap<ViewportId, ViewportBuilder>> =
+                    std::cell::RefCell::new(HashMap::new());
+            }
+
+            /// Take the latest builders of deferred viewports, to apply them to the viewport windows with [TrackedWindowContainer::update_viewport_builder]
+            pub fn take_viewport_builders() -> HashMap<ViewportId, ViewportBuilder> {
+                VIEWPORT_BUILDERS.with(|b| std::mem::take(&mut *b.borrow_mut()))
+            }
+
+            /// A window for an immediate viewport
+            struct ImmediateViewportWindow {
+                /// The window that shows the viewport, it is redrawn when this window redraws
+                parent: WindowId,
+                /// The container for the window
+                container: TrackedWindowContainer,
+            }
+
+            /// Stop handing out the share group of a window whose context was lost or destroyed, later windows of the group start a new share group
+            fn forget_share_group(window: &winit::window::Window, options: &TrackedWindowOptions) {
+                if let Some(group) = options.share_group {
+                    SHARE_GROUPS.with(|g| g.borrow_mut().remove(&(group, window.raw_display_handle())));
+                }
+            }
+
+            /// The renderer for egui immediate viewports. The ui of the viewport is run synchronously on the egui context of its own window,
+            /// then the window is painted. This leaves the opengl context of the viewport current.
+            fn render_immediate_viewport(_ctx: &egui::Context, viewport: egui::viewport::ImmediateViewport<'_>) {
+                let id = viewport.ids.this;
+                let entry = IMMEDIATE_VIEWPORTS.with(|m| m.borrow_mut().remove(&id));
+                // The window for a new viewport is created after the frame of the parent, so the ui of the viewport is skipped
+                // for that one frame. It runs on the next frame of the parent, once the window exists.
+                if let Some(mut entry) = entry {
+                    entry.container.render_immediate(viewport.viewport_ui_cb);
+                    IMMEDIATE_VIEWPORTS.with(|m| m.borrow_mut().insert(id, entry));
+                }
+            }
+
+            /// Creates windows for new immediate viewports shown by the parent window and destroys windows for immediate viewports no longer shown.
+            /// Returns true if the opengl context of the parent window needs to be made current again.
+            fn update_immediate_viewports(
+                parent: WindowId,
+                parent_ctx: &egui::Context,
+                viewportset: &Arc<Mutex<ViewportIdSet>>,
+                output: &egui::viewport::ViewportIdMap<egui::viewport::ViewportOutput>,
+                el: &EventLoopWindowTarget<$event>,
+            ) -> bool {
+                let mut changed = false;
+                for (id, vo) in output {
+                    if !matches!(vo.class, egui::viewport::ViewportClass::Immediate)
+                        || IMMEDIATE_VIEWPORTS.with(|m| m.borrow().contains_key(id))
+                    {
+                        continue;
+                    }
+                    let wb = egui_multiwin::egui_glow::egui_winit::create_winit_window_builder(
+                        parent_ctx,
+                        el,
+                        vo.builder.to_owned(),
+                    );
+                    let container = TrackedWindowContainer::create(
+                        None,
+                        viewportset.to_owned(),
+                        id,
+                        None,
+                        wb,
+                        el,
+                        &TrackedWindowOptions::default(),
+                        Some(vo.builder.to_owned()),
+                    );
+                    if let Ok(mut container) = container {
+                        let gl_window = mem::replace(container.gl_window_mut(), IndeterminateWindowedContext::None);
+                        if let IndeterminateWindowedContext::NotCurrent(w) = gl_window {
+                            if let Ok(w) = w.make_current() {
+                                let gl = Arc::new(unsafe {
+                                    glow::Context::from_loader_function(|s| w.get_proc_address(s))
+                                });
+                                unsafe {
+                                    use glow::HasContext as _;
+                                    // sRGB framebuffers are not part of OpenGL ES
+                                    if w.srgb() && !gl.version().is_embedded {
+                                        gl.enable(glow::FRAMEBUFFER_SRGB);
+                                    }
+                                }
+                                let egui = egui_glow::EguiGlow::new(el, gl, None, None);
+                                egui.egui_ctx.set_style(parent_ctx.style());
+                                egui.egui_ctx.set_embed_viewports(false);
+                                container.common_mut().egui = Some(egui);
+                                *container.gl_window_mut() = IndeterminateWindowedContext::PossiblyCurrent(w);
+                                IMMEDIATE_VIEWPORTS.with(|m| {
+                                    m.borrow_mut().insert(*id, ImmediateViewportWindow { parent, container })
+                                });
+                            }
+                        }
+                    }
+                    changed = true;
+                }
+                IMMEDIATE_VIEWPORTS.with(|m| {
+                    let mut m = m.borrow_mut();
+                    let closed: Vec<ViewportId> = m
+                        .iter()
+                        .filter(|(id, e)| e.parent == parent && !output.contains_key(id))
+                        .map(|(id, _)| *id)
+                        .collect();
+                    for id in closed {
+                        if let Some(mut entry) = m.remove(&id) {
+                            let common = entry.container.common_mut();
+                            if let (IndeterminateWindowedContext::PossiblyCurrent(w), Some(egui)) =
+                                (&common.gl_window, &mut common.egui)
+                            {
+                                let _e = w.make_current();
+                                egui.destroy();
+                            }
+                            changed = true;
+                        }
+                    }
+                });
+                changed
+            }
+
+            /// Passes a window event to the immediate viewport window it belongs to.
+            /// Returns the id of the parent window, which must be redrawn to update the viewport.
+            pub fn immediate_viewport_event(
+                window_id: WindowId,
+                event: &winit::event::WindowEvent,
+            ) -> Option<WindowId> {
+                IMMEDIATE_VIEWPORTS.with(|m| {
+                    let mut m = m.borrow_mut();
+                    let entry = m
+                        .values_mut()
+                        .find(|e| e.container.get_window_id() == Some(window_id))?;
+                    let common = entry.container.common_mut();
+                    if let (IndeterminateWindowedContext::PossiblyCurrent(w), Some(egui)) =
+                        (&common.gl_window, &mut common.egui)
+                    {
+                        if let winit::event::WindowEvent::Resized(size) = event {
+                            w.resize(*size);
+                        }
+                        let _ = egui.on_window_event(w.window(), event);
+                    }
+                    Some(entry.parent)
+                })
+            }
+
             /// Enum of the potential options for a window context
             pub enum IndeterminateWindowedContext {
                 /// The window context is possibly current
                 PossiblyCurrent(ContextHolder<PossiblyCurrentContext>),
                 /// The window context is not current
                 NotCurrent(ContextHolder<NotCurrentContext>),
+                /// The window is suspended, its opengl surface and context have been destroyed until the application is resumed
+                Suspended(winit::window::Window, TrackedWindowOptions),
                 /// The window context is empty
                 None,
             }
@@ -783,9 +2365,36 @@ macro_rules! tracked_window {
                     match self {
                         IndeterminateWindowedContext::PossiblyCurrent(pc) => pc.window(),
                         IndeterminateWindowedContext::NotCurrent(nc) => nc.window(),
+                        IndeterminateWindowedContext::Suspended(w, _) => w,
                         IndeterminateWindowedContext::None => panic!("No window"),
                     }
                 }
+
+                /// Get the window handle, if there is a window
+                pub fn window_opt(&self) -> Option<&winit::window::Window> {
+                    match self {
+                        IndeterminateWindowedContext::None => None,
+                        w => Some(w.window()),
+                    }
+                }
+            }
+
+            /// The settings of a `MultiWindow` that are applied to the egui instance of a window when it is created. Used internally for window management.
+            pub struct EguiSettings<'a> {
+                /// The fonts to install
+                pub fonts: &'a egui::FontDefinitions,
+                /// True when animations are disabled, see `MultiWindow::set_reduced_motion`
+                pub reduced_motion: bool,
+                /// True when the image loaders of egui_extras are installed
+                pub image_loaders: bool,
+                /// True when the debug painting of egui is enabled
+                pub debug: bool,
+                /// The visuals to apply, if set
+                pub visuals: Option<&'a egui::Visuals>,
+                /// The tessellation options to apply, if set
+                pub tessellation_options: Option<&'a egui::epaint::TessellationOptions>,
+                /// A proxy for the event loop, used to deliver accesskit action requests
+                pub proxy: Option<&'a winit::event_loop::EventLoopProxy<$event>>,
             }
 
             /// The eventual return struct of the `TrackedWindow` trait update function. Used internally for window management.
@@ -798,7 +2407,27 @@ macro_rules! tracked_window {
 
             #[derive(egui_multiwin::thiserror::Error, Debug)]
             /// Enumerates the kinds of errors that display creation can have.
-            pub enum DisplayCreationError {}
+            /// These allow an application to detect that opengl is unavailable and degrade gracefully instead of crashing.
+            pub enum DisplayCreationError {
+                /// The winit window could not be created
+                #[error("Unable to create the window")]
+                WindowCreation(egui_multiwin::winit::error::OsError),
+                /// The opengl api requested with the gl_api_preference option is not available on this platform
+                #[error("The requested opengl api is not available on this platform")]
+                UnsupportedApi(egui_multiwin::tracked_window::GlApiPreference),
+                /// No opengl display could be created for the window
+                #[error("Unable to create an opengl display")]
+                NoDisplay(glutin::error::Error),
+                /// The opengl display did not provide any configurations
+                #[error("Unable to find opengl configurations")]
+                NoConfigs(glutin::error::Error),
+                /// None of the available configurations could create a window surface, even with a relaxed template. Contains a description of the templates attempted.
+                #[error("No opengl configuration can create a surface for the window")]
+                NoGlConfig(String),
+                /// The opengl context could not be created
+                #[error("Unable to create an opengl context")]
+                NoContext(glutin::error::Error),
+            }
         }
     };
 }
@@ -810,7 +2439,6 @@ macro_rules! multi_window {
         pub mod multi_window {
             //! This defines the MultiWindow struct. This is the main struct used in the main function of a user application.
 
-            use std::collections::HashMap;
             use std::sync::{Arc, Mutex};
 
             use egui_multiwin::{
@@ -826,18 +2454,109 @@ macro_rules! multi_window {
             use egui_multiwin::egui;
 
             use super::tracked_window::{
-                DisplayCreationError, TrackedWindow, TrackedWindowContainer,
+                DisplayCreationError, EguiSettings, TrackedWindow, TrackedWindowContainer,
             };
 
+            /// The trait that the common data struct of the application implements, for handling events that are not specific to a window.
+            pub trait CommonEventHandler {
+                /// Process a custom event that is not targeted at a specific window, or that is for a window that does not exist.
+                /// Returns a list of windows to create.
+                fn process_event(&mut self, event: $event) -> Vec<NewWindowRequest>;
+
+                /// Returns true when the application should exit. This is checked after every call to process_event, allowing a custom event to quit the application.
+                /// The default is to never request an exit.
+                fn should_quit(&self) -> bool {
+                    false
+                }
+
+                /// Called when a deferred viewport window closes, either because the user closed it or because it is no longer shown.
+                /// Does nothing by default.
+                fn viewport_closed(&mut self, _id: ViewportId) {}
+
+                /// Called once per iteration of the event loop, after all pending events have been processed and before the event loop waits.
+                /// Allows periodic work without a window that redraws continuously. Returns when idle should be called next, which is combined
+                /// with the control flow requested by the windows. The default returns None, not requesting a wake up.
+                fn idle(&mut self) -> Option<std::time::Instant> {
+                    None
+                }
+
+                /// Called for every device event, such as raw mouse motion, which is not tied to a window. Useful for camera controls.
+                /// Which device events are delivered is set with `MultiWindow::set_device_events`. Does nothing by default.
+                fn device_event(&mut self, _event: &winit::event::DeviceEvent) {}
+
+                /// Called once when the event loop exits, on the main thread, right before `MultiWindow::run` returns. This happens when the
+                /// winit `LoopExiting` event arrives, before the windows that are still open receive it and destroy their egui instances.
+                /// Windows that were closed earlier are already destroyed. Use this for final cleanup like saving state, instead of relying on
+                /// dropping the common data, which happens after the event loop is gone. Does nothing by default.
+                fn on_exit(&mut self) {}
+            }
+
+            thread_local! {
+                /// Messages posted with [post_to_window] that have not been delivered yet, with the window request id they are for
+                static MAILBOX: std::cell::RefCell<Vec<(u32, $event)>> = std::cell::RefCell::new(Vec::new());
+            }
+
+            /// Queue a message for a window, specified by the window request id. The message is delivered to the `custom_event` function of the window
+            /// after the event loop handles its current event, without needing the event loop proxy or the winit window id.
+            /// Messages can be posted from windows and from the common data, they must be posted from the thread running the event loop.
+            /// Use the event loop proxy to send events from other threads. Messages for windows that do not exist are dropped.
+            pub fn post_to_window(id: u32, msg: $event) {
+                MAILBOX.with(|m| m.borrow_mut().push((id, msg)));
+            }
+
+            thread_local! {
+                /// Window data waiting to replace the data of a window, with the window request id of the window
+                static REPLACEMENTS: std::cell::RefCell<Vec<(u32, $window)>> = std::cell::RefCell::new(Vec::new());
+            }
+
+            /// Queue a replacement of the window data of a window, specified by the window request id, see [MultiWindow::replace_window_state].
+            /// This can be called from the redraw function of the window itself, for example to turn a login window into the main window.
+            /// The replacement is done after the event loop handles its current event, the replaced window data is dropped.
+            pub fn replace_window_state(id: u32, state: $window) {
+                REPLACEMENTS.with(|r| r.borrow_mut().push((id, state)));
+            }
+
             /// The main struct of the crate. Manages multiple `TrackedWindow`s by forwarding events to them.
             /// `T` represents the common data struct for the user program. `U` is the type representing custom events.
             pub struct MultiWindow {
                 /// The windows for the application.
                 windows: Vec<TrackedWindowContainer>,
-                /// A list of fonts to install on every egui instance
-                fonts: HashMap<String, egui_multiwin::egui::FontData>,
+                /// The fonts to install on every egui instance
+                fonts: egui_multiwin::egui::FontDefinitions,
                 /// The clipboard
-                clipboard: egui_multiwin::arboard::Clipboard,
+                clipboard: Box<dyn egui_multiwin::clipboard::ClipboardProvider>,
+                /// True when animations should be disabled for all windows
+                reduced_motion: bool,
+                /// The visuals applied to every window, if set
+                visuals: Option<egui::Visuals>,
+                /// The tessellation options for all windows, None for the egui defaults
+                tessellation_options: Option<egui::epaint::TessellationOptions>,
+                /// The aggregate input state of the application
+                input: egui_multiwin::multi_window::InputSnapshot,
+                /// How long to wait for a new window after the last window closes, before exiting
+                exit_grace_period: Option<std::time::Duration>,
+                /// When the application will exit, if no new windows are created
+                exit_deadline: Option<std::time::Instant>,
+                /// Synthetic window events waiting to be processed
+                posted_events: Vec<(winit::window::WindowId, winit::event::WindowEvent)>,
+                /// A proxy for the event loop, used to deliver accesskit action requests to windows and by repaint signals
+                proxy: Option<winit::event_loop::EventLoopProxy<$event>>,
+                /// The first window added, which acts as the root window when no window is marked as root
+                first_window: Option<winit::window::WindowId>,
+                /// Set when the application should quit
+                quit_requested: egui_multiwin::multi_window::QuitHandle,
+                /// Repaints requested from any thread with a [egui_multiwin::multi_window::RepaintSignal]
+                repaint_queue: egui_multiwin::multi_window::RepaintQueue,
+                /// Decides when the application exits as windows close
+                exit_policy: egui_multiwin::multi_window::ExitPolicy,
+                /// A change of which device events are delivered, applied when the event loop handles its next event
+                device_events: Option<winit::event_loop::DeviceEvents>,
+                /// True when idle windows are not redrawn, see [MultiWindow::set_power_saving]
+                power_saving: bool,
+                /// True when image loaders are installed on every window
+                image_loaders: bool,
+                /// True when the debug painting of egui is enabled on every window
+                debug: bool,
             }
 
             impl Default for MultiWindow {
@@ -851,76 +2570,713 @@ macro_rules! multi_window {
                 pub fn new() -> Self {
                     MultiWindow {
                         windows: vec![],
-                        fonts: HashMap::new(),
-                        clipboard: egui_multiwin::arboard::Clipboard::new().unwrap(),
+                        fonts: egui_multiwin::egui::FontDefinitions::default(),
+                        clipboard: egui_multiwin::clipboard::system_clipboard(),
+                        reduced_motion: false,
+                        visuals: None,
+                        tessellation_options: None,
+                        input: egui_multiwin::multi_window::InputSnapshot::default(),
+                        exit_grace_period: None,
+                        exit_deadline: None,
+                        first_window: None,
+                        proxy: None,
+                        posted_events: Vec::new(),
+                        quit_requested: egui_multiwin::multi_window::QuitHandle::default(),
+                        repaint_queue: egui_multiwin::multi_window::RepaintQueue::default(),
+                        exit_policy: egui_multiwin::multi_window::ExitPolicy::default(),
+                        device_events: None,
+                        power_saving: false,
+                        image_loaders: false,
+                        debug: false,
                     }
                 }
 
-                /// A simpler way to start up a user application. The provided closure should initialize the root window, add any fonts desired, store the proxy if it is needed, and return the common app struct.
-                pub fn start(
-                    t: impl FnOnce(
-                        &mut Self,
-                        &EventLoop<$event>,
-                        egui_multiwin::winit::event_loop::EventLoopProxy<$event>,
-                    ) -> $common,
-                ) -> Result<(), EventLoopError> {
-                    let mut event_loop =
-                        egui_multiwin::winit::event_loop::EventLoopBuilder::with_user_event();
-                    let event_loop = event_loop.build().unwrap();
-                    let proxy = event_loop.create_proxy();
-                    let mut multi_window = Self::new();
+                /// Set which device events are delivered to `CommonEventHandler::device_event`. Use `DeviceEvents::Always` to receive raw mouse motion
+                /// even when no window of the application is focused. winit delivers device events only while a window is focused by default.
+                pub fn set_device_events(&mut self, device_events: winit::event_loop::DeviceEvents) {
+                    self.device_events = Some(device_events);
+                }
 
-                    let ac = t(&mut multi_window, &event_loop, proxy);
+                /// Enable or disable power saving. Normally an animating window makes the event loop poll, and whenever a repaint time is
+                /// reached every window is redrawn. With power saving, only the windows that egui asked to repaint are redrawn, and
+                /// animating windows wait for their redraw requests instead of polling, so idle windows sleep. Disabled by default.
+                pub fn set_power_saving(&mut self, enabled: bool) {
+                    self.power_saving = enabled;
+                }
 
-                    multi_window.run(event_loop, ac)
+                /// Set when the application exits as windows close. The default is [egui_multiwin::multi_window::ExitPolicy::WhenNoRoots].
+                pub fn set_exit_policy(&mut self, policy: egui_multiwin::multi_window::ExitPolicy) {
+                    self.exit_policy = policy;
                 }
 
-                /// Add a font that is applied to every window. Be sure to call this before calling [add](crate::multi_window::MultiWindow::add)
-                /// multi_window is an instance of [MultiWindow](crate::multi_window::MultiWindow), DATA is a static `&[u8]` - most like defined with a `include_bytes!()` macro
-                /// ```
-                /// use egui_multiwin::multi_window::NewWindowRequest;
-                /// struct Custom {}
-                ///
-                /// impl egui_multiwin::multi_window::CommonEventHandler for Custom {
-                ///     fn process_event(&mut self, _event: egui_multiwin::multi_window::DefaultCustomEvent)  -> Vec<NewWindowRequest>{
-                ///         vec!()
-                ///     }
-                /// }
-                ///
-                /// let mut multi_window: egui_multiwin::multi_window::MultiWindow = egui_multiwin::multi_window::MultiWindow::new();
-                /// let DATA = include_bytes!("cmunbtl.ttf");
-                /// multi_window.add_font("my_font".to_string(), egui_multiwin::egui::FontData::from_static(DATA));
+                /// Request the application to quit. Every window is asked with `can_quit`, windows that allow it are closed, and the event loop
+                /// exits once no windows are left. A window that vetoes by returning false from `can_quit` stays open (along with the application),
+                /// the request is not repeated, so quit must be requested again after the window is ready to close.
+                /// The request is handled after the event loop processes its current event.
+                pub fn quit(&self) {
+                    self.quit_requested.quit();
+                }
+
+                /// Get a handle that can request the application to quit, like [Self::quit]. Store it in the common data to quit from a window.
+                pub fn quit_handle(&self) -> egui_multiwin::multi_window::QuitHandle {
+                    self.quit_requested.clone()
+                }
+
+                /// Get a handle that can repaint windows from any thread. The event created by wake is sent to wake up the event loop,
+                /// see [egui_multiwin::multi_window::RepaintSignal]. Returns None before the event loop exists, it is available from
+                /// the closure given to [Self::start] and once [Self::run] is called.
+                /// ```ignore
+                /// let signal = multi_window.repaint_signal(|| egui_multiwin::NoEvent {}).unwrap();
+                /// std::thread::spawn(move || {
+                ///     // Do some work, then show the result
+                ///     signal.request_repaint(id);
+                /// });
                 /// ```
-                pub fn add_font(&mut self, name: String, fd: egui_multiwin::egui::FontData) {
-                    self.fonts.insert(name, fd);
+                pub fn repaint_signal(
+                    &self,
+                    wake: impl Fn() -> $event + Send + Sync + 'static,
+                ) -> Option<egui_multiwin::multi_window::RepaintSignal<$event>> {
+                    let proxy = self.proxy.clone()?;
+                    Some(egui_multiwin::multi_window::RepaintSignal::new(
+                        self.repaint_queue.clone(),
+                        proxy,
+                        wake,
+                    ))
                 }
 
-                /// Adds a new `TrackedWindow` to the `MultiWindow`. If custom fonts are desired, call [add_font](crate::multi_window::MultiWindow::add_font) first.
-                pub fn add<TE>(
-                    &mut self,
-                    window: NewWindowRequest,
-                    _c: &mut $common,
-                    event_loop: &egui_multiwin::winit::event_loop::EventLoopWindowTarget<TE>,
-                ) -> Result<(), DisplayCreationError> {
-                    let twc = TrackedWindowContainer::create::<TE>(
-                        window.window_state,
-                        window.viewportset,
-                        &window
-                            .viewport_id
-                            .unwrap_or(egui::viewport::ViewportId::ROOT),
-                        window.viewport_callback,
+                /// Set a grace period to wait after the last window closes before the event loop exits.
+                /// Events (such as custom events from other threads) are still processed during this time, and if a new window is created the exit is cancelled.
+                /// None (the default) exits immediately.
+                pub fn set_exit_grace_period(&mut self, grace: Option<std::time::Duration>) {
+                    self.exit_grace_period = grace;
+                }
+
+                /// Use a custom clipboard backend instead of the system clipboard
+                pub fn with_clipboard(
+                    mut self,
+                    clipboard: impl egui_multiwin::clipboard::ClipboardProvider + 'static,
+                ) -> Self {
+                    self.clipboard = Box::new(clipboard);
+                    self
+                }
+
+                /// Get the current input state (held modifiers, held mouse buttons, and the focused window) across all windows.
+                pub fn input_state(&self) -> egui_multiwin::multi_window::InputSnapshot {
+                    self.input.clone()
+                }
+
+                /// Set the visuals (such as dark or light mode) of all current and future windows.
+                pub fn set_visuals(&mut self, visuals: egui::Visuals) {
+                    for w in &self.windows {
+                        if let Some(egui) = &w.common().egui {
+                            egui.egui_ctx.set_visuals(visuals.clone());
+                        }
+                        if let Some(window) = w.window() {
+                            window.request_redraw();
+                        }
+                    }
+                    self.visuals = Some(visuals);
+                }
+
+                /// Set the tessellation options of all current and future windows. Turning off feathering gives crisp edges for
+                /// pixel perfect interfaces, and is cheaper on slow hardware.
+                /// ```ignore
+                /// let mut options = egui_multiwin::egui::epaint::TessellationOptions::default();
+                /// options.feathering = false;
+                /// multi_window.set_tessellation_options(options);
+                /// ```
+                pub fn set_tessellation_options(&mut self, options: egui::epaint::TessellationOptions) {
+                    for w in &self.windows {
+                        if let Some(egui) = &w.common().egui {
+                            egui.egui_ctx.tessellation_options_mut(|o| *o = options);
+                        }
+                        if let Some(window) = w.window() {
+                            window.request_redraw();
+                        }
+                    }
+                    self.tessellation_options = Some(options);
+                }
+
+                /// Returns true when the operating system requests reduced motion. This is a best-effort check, see [egui_multiwin::accessibility::prefers_reduced_motion].
+                pub fn prefers_reduced_motion() -> bool {
+                    egui_multiwin::accessibility::prefers_reduced_motion()
+                }
+
+                /// Enable or disable egui animations for all current and future windows.
+                /// Call with the result of [Self::prefers_reduced_motion] to follow the operating system setting.
+                pub fn set_reduced_motion(&mut self, reduced: bool) {
+                    self.reduced_motion = reduced;
+                    for w in &mut self.windows {
+                        if let Some(egui) = &w.common().egui {
+                            super::tracked_window::set_reduced_motion(&egui.egui_ctx, reduced);
+                        }
+                        if let Some(window) = w.window() {
+                            window.request_redraw();
+                        }
+                    }
+                }
+
+                /// Enable or disable the debug painting of egui on all current and future windows, useful while developing a layout.
+                /// Nothing is done for new windows while it is off.
+                pub fn set_debug(&mut self, on: bool) {
+                    self.debug = on;
+                    for w in &mut self.windows {
+                        if let Some(egui) = &w.common().egui {
+                            super::tracked_window::set_debug(&egui.egui_ctx, on);
+                        }
+                    }
+                }
+
+                /// Install the image loaders of egui_extras on all current and future windows, so images can be shown by uri,
+                /// like `ui.image("file://image.png")`. Requires the `image_loaders` feature, see [egui_multiwin::tracked_window::install_image_loaders].
+                pub fn install_image_loaders(&mut self) {
+                    self.image_loaders = true;
+                    for w in &self.windows {
+                        if let Some(egui) = &w.common().egui {
+                            egui_multiwin::tracked_window::install_image_loaders(&egui.egui_ctx);
+                        }
+                    }
+                }
+
+                /// A simpler way to start up a user application. The provided closure should initialize the root window, add any fonts desired, store the proxy if it is needed, and return the common app struct.
+                /// If none of the windows is a root window, the first window added acts as the root window: the application keeps running while it is open,
+                /// and the other windows close when it closes.
+                pub fn start(
+                    t: impl FnOnce(
+                        &mut Self,
+                        &EventLoop<$event>,
+                        egui_multiwin::winit::event_loop::EventLoopProxy<$event>,
+                    ) -> $common,
+                ) -> Result<(), EventLoopError> {
+                    Self::start_with(|_| {}, t)
+                }
+
+                /// Like [Self::start], but the event loop builder is passed to builder_cfg before the event loop is built.
+                /// This allows platform specific options, such as forcing x11 with `EventLoopBuilderExtX11::with_x11`.
+                pub fn start_with(
+                    builder_cfg: impl FnOnce(&mut egui_multiwin::winit::event_loop::EventLoopBuilder<$event>),
+                    t: impl FnOnce(
+                        &mut Self,
+                        &EventLoop<$event>,
+                        egui_multiwin::winit::event_loop::EventLoopProxy<$event>,
+                    ) -> $common,
+                ) -> Result<(), EventLoopError> {
+                    let mut event_loop =
+                        egui_multiwin::winit::event_loop::EventLoopBuilder::with_user_event();
+                    builder_cfg(&mut event_loop);
+                    let event_loop = event_loop.build()?;
+                    let proxy = event_loop.create_proxy();
+                    let mut multi_window = Self::new();
+                    multi_window.proxy = Some(proxy.clone());
+
+                    let ac = t(&mut multi_window, &event_loop, proxy);
+
+                    multi_window.run(event_loop, ac)
+                }
+
+                /// Add a font that is applied to every window. Windows that are already open are updated as well.
+                /// multi_window is an instance of [MultiWindow](crate::multi_window::MultiWindow), DATA is a static `&[u8]` - most like defined with a `include_bytes!()` macro
+                /// ```
+                /// use egui_multiwin::multi_window::NewWindowRequest;
+                /// struct Custom {}
+                ///
+                /// impl egui_multiwin::multi_window::CommonEventHandler for Custom {
+                ///     fn process_event(&mut self, _event: egui_multiwin::multi_window::DefaultCustomEvent)  -> Vec<NewWindowRequest>{
+                ///         vec!()
+                ///     }
+                /// }
+                ///
+                /// let mut multi_window: egui_multiwin::multi_window::MultiWindow = egui_multiwin::multi_window::MultiWindow::new();
+                /// let DATA = include_bytes!("cmunbtl.ttf");
+                /// multi_window.add_font("my_font".to_string(), egui_multiwin::egui::FontData::from_static(DATA));
+                /// ```
+                pub fn add_font(&mut self, name: String, fd: egui_multiwin::egui::FontData) {
+                    self.fonts.font_data.insert(name.clone(), fd);
+                    self.fonts.families.insert(
+                        egui::FontFamily::Name(name.to_owned().into()),
+                        vec![name],
+                    );
+                    self.apply_fonts();
+                }
+
+                /// Remove a font previously added with [Self::add_font]. Windows that are already open are updated as well.
+                pub fn remove_font(&mut self, name: &str) {
+                    self.fonts.font_data.remove(name);
+                    self.fonts
+                        .families
+                        .remove(&egui::FontFamily::Name(name.into()));
+                    for family in self.fonts.families.values_mut() {
+                        family.retain(|f| f != name);
+                    }
+                    self.apply_fonts();
+                }
+
+                /// Install the current fonts on every window that has already been created
+                fn apply_fonts(&self) {
+                    for w in &self.windows {
+                        if let Some(egui) = &w.common().egui {
+                            egui.egui_ctx.set_fonts(self.fonts.clone());
+                        }
+                        if let Some(window) = w.window() {
+                            window.request_redraw();
+                        }
+                    }
+                }
+
+                /// Find the window container for the given window request id
+                fn find_window(&self, id: u32) -> Option<&TrackedWindowContainer> {
+                    let wid = egui_multiwin::multi_window::get_window_id(id)?;
+                    self.windows.iter().find(|w| w.get_window_id() == Some(wid))
+                }
+
+                /// Request a redraw of a specific window, specified by the window request id. Does nothing if the window does not exist.
+                pub fn request_redraw(&self, id: u32) {
+                    if let Some(window) = self.find_window(id).and_then(|w| w.window()) {
+                        window.request_redraw();
+                    }
+                }
+
+                /// Get the list of all monitors, for use with [NewWindowRequest::centered_on]
+                pub fn monitors(
+                    event_loop: &winit::event_loop::EventLoopWindowTarget<$event>,
+                ) -> Vec<winit::monitor::MonitorHandle> {
+                    event_loop.available_monitors().collect()
+                }
+
+                /// Get the primary monitor, if the platform has the concept of one. Falls back to the first monitor otherwise.
+                pub fn primary_monitor(
+                    event_loop: &winit::event_loop::EventLoopWindowTarget<$event>,
+                ) -> Option<winit::monitor::MonitorHandle> {
+                    event_loop
+                        .primary_monitor()
+                        .or_else(|| event_loop.available_monitors().next())
+                }
+
+                /// Save the geometry of all open windows, so it can be restored with [NewWindowRequest::with_geometry].
+                pub fn save_layout(&self) -> egui_multiwin::multi_window::Layout {
+                    let table = egui_multiwin::multi_window::WINDOW_TABLE
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner());
+                    let mut layout = egui_multiwin::multi_window::Layout::default();
+                    for (id, wid) in table.iter() {
+                        let window = self
+                            .windows
+                            .iter()
+                            .find(|w| wid.is_some() && w.get_window_id() == *wid)
+                            .and_then(|w| w.window());
+                        if let Some(window) = window {
+                            layout.windows.push(egui_multiwin::multi_window::LayoutWindow {
+                                id: *id,
+                                title: window.title(),
+                                geometry: egui_multiwin::multi_window::WindowGeometry::from_window(window),
+                            });
+                        }
+                    }
+                    layout.windows.sort_by_key(|w| w.id);
+                    layout
+                }
+
+                /// Save which windows are open, by role, along with their geometry. Windows without a role are not included.
+                /// Restore the windows with [Self::restore_layout].
+                pub fn serialize_layout(&self) -> egui_multiwin::multi_window::LayoutSnapshot {
+                    let mut snapshot = egui_multiwin::multi_window::LayoutSnapshot::default();
+                    for w in &self.windows {
+                        if let (Some(role), Some(window)) = (&w.common().role, w.window()) {
+                            snapshot.windows.push(egui_multiwin::multi_window::LayoutSnapshotWindow {
+                                role: role.to_owned(),
+                                geometry: egui_multiwin::multi_window::WindowGeometry::from_window(window),
+                            });
+                        }
+                    }
+                    snapshot
+                }
+
+                /// Reopen the windows of a snapshot made with [Self::serialize_layout]. The factory creates the request for a window from its role,
+                /// returning None for roles the application no longer knows, which are skipped. The saved geometry is applied to each request,
+                /// and a window is not opened twice when a window with the role is already open. Returns the window request ids of the windows.
+                pub fn restore_layout<TE>(
+                    &mut self,
+                    snapshot: &egui_multiwin::multi_window::LayoutSnapshot,
+                    factory: impl Fn(&str) -> Option<NewWindowRequest>,
+                    c: &mut $common,
+                    event_loop: &egui_multiwin::winit::event_loop::EventLoopWindowTarget<TE>,
+                ) -> Result<Vec<u32>, DisplayCreationError> {
+                    let mut ids = Vec::new();
+                    for w in &snapshot.windows {
+                        if let Some(request) = factory(&w.role) {
+                            let request = request.with_geometry(&w.geometry);
+                            ids.push(self.add_or_focus(request, &w.role, c, event_loop)?);
+                        }
+                    }
+                    Ok(ids)
+                }
+
+                /// Get the raw window handle and raw display handle of a window, specified by the window request id.
+                /// The handles are only valid while the window exists, they must not be used after the window is closed.
+                pub fn raw_handles(
+                    &self,
+                    id: u32,
+                ) -> Option<(
+                    egui_multiwin::raw_window_handle_5::RawWindowHandle,
+                    egui_multiwin::raw_window_handle_5::RawDisplayHandle,
+                )> {
+                    let w = self.find_window(id)?;
+                    Some((w.raw_window_handle()?, w.raw_display_handle()?))
+                }
+
+                /// Mark a window, specified by the window request id, as protected so it is excluded from screenshots and screen recording.
+                /// This is supported on windows and macos. On other platforms nothing is done and [egui_multiwin::multi_window::WindowError::Unsupported] is returned.
+                pub fn set_content_protected(
+                    &self,
+                    id: u32,
+                    protected: bool,
+                ) -> Result<(), egui_multiwin::multi_window::WindowError> {
+                    let window = self
+                        .find_window(id)
+                        .and_then(|w| w.window())
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    if cfg!(any(target_os = "windows", target_os = "macos")) {
+                        window.set_content_protected(protected);
+                        Ok(())
+                    } else {
+                        Err(egui_multiwin::multi_window::WindowError::Unsupported)
+                    }
+                }
+
+                /// Replace the window data of a window, specified by the window request id, keeping the native window and its opengl context.
+                /// This avoids the flash and the cost of closing the window and opening a new one. Returns the previous window data.
+                /// Returns [egui_multiwin::multi_window::WindowError::Unsupported] for viewport windows, which have no window data.
+                pub fn replace_window_state(
+                    &mut self,
+                    id: u32,
+                    state: $window,
+                ) -> Result<$window, egui_multiwin::multi_window::WindowError> {
+                    let wid = egui_multiwin::multi_window::get_window_id(id)
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    let window = self
+                        .windows
+                        .iter_mut()
+                        .find(|w| w.get_window_id() == Some(wid))
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    window
+                        .replace_window_data(state)
+                        .map_err(|_| egui_multiwin::multi_window::WindowError::Unsupported)
+                }
+
+                /// Capture the next frame painted for a window, specified by the window request id. The frame is read back from the
+                /// window before it is presented, get it with [Self::take_capture] once the window has been redrawn.
+                pub fn request_capture(&mut self, id: u32) -> Result<(), egui_multiwin::multi_window::WindowError> {
+                    let wid = egui_multiwin::multi_window::get_window_id(id)
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    self.windows
+                        .iter_mut()
+                        .find(|w| w.get_window_id() == Some(wid))
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?
+                        .request_capture();
+                    Ok(())
+                }
+
+                /// Take the frame of a window, specified by the window request id, captured after [Self::request_capture].
+                /// Returns None if the window does not exist or no frame has been captured since the last call.
+                pub fn take_capture(&mut self, id: u32) -> Option<egui::ColorImage> {
+                    let wid = egui_multiwin::multi_window::get_window_id(id)?;
+                    self.windows.iter_mut().find(|w| w.get_window_id() == Some(wid))?.take_capture()
+                }
+
+                /// Run a closure with the egui context of a window, specified by the window request id, outside of redraw.
+                /// The opengl context of the window is made current first, so textures can be allocated from the closure.
+                /// Returns [egui_multiwin::multi_window::WindowError::NotFound] if the window does not exist or has not been initialized yet.
+                pub fn with_egui_ctx(
+                    &mut self,
+                    id: u32,
+                    f: impl FnOnce(&egui::Context),
+                ) -> Result<(), egui_multiwin::multi_window::WindowError> {
+                    let window = self
+                        .find_window(id)
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    window
+                        .with_egui_ctx(f)
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    if let Some(w) = window.window() {
+                        w.request_redraw();
+                    }
+                    Ok(())
+                }
+
+                /// Close a window, specified by the window request id, without asking the window. This is used to finish closing a window
+                /// that kept itself open by returning false from on_close_requested, once the user has confirmed.
+                pub fn confirm_close(&mut self, id: u32) -> Result<(), egui_multiwin::multi_window::WindowError> {
+                    let wid = egui_multiwin::multi_window::get_window_id(id)
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    let index = self
+                        .windows
+                        .iter()
+                        .position(|w| w.get_window_id() == Some(wid))
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    let mut window = self.windows.remove(index);
+                    window.close_viewport();
+                    window.destroy();
+                    self.close_children(vec![wid]);
+                    Ok(())
+                }
+
+                /// Close every window matching the predicate that agrees to close, by returning true from can_quit. Viewports are skipped,
+                /// as they have no window data. The children of closed windows are closed too. Returns how many matching windows were closed.
+                /// ```ignore
+                /// // Close all tool windows, keeping the main window
+                /// multi_window.close_if(&mut ac, |w| !w.is_root());
+                /// ```
+                pub fn close_if(&mut self, c: &mut $common, pred: impl Fn(&$window) -> bool) -> usize {
+                    let mut closed = Vec::new();
+                    let mut count = 0;
+                    let mut index = 0;
+                    while index < self.windows.len() {
+                        let close = match self.windows[index].get_window_data_mut() {
+                            Some(w) => pred(w) && w.can_quit(c),
+                            None => false,
+                        };
+                        if close {
+                            let mut window = self.windows.remove(index);
+                            window.close_viewport();
+                            window.destroy();
+                            closed.extend(window.get_window_id());
+                            count += 1;
+                        } else {
+                            index += 1;
+                        }
+                    }
+                    self.close_children(closed);
+                    count
+                }
+
+                /// Close all descendants of the given closed windows. Children are closed without asking them, because their parent is gone.
+                fn close_children(&mut self, mut closed: Vec<winit::window::WindowId>) {
+                    while let Some(parent) = closed.pop() {
+                        while let Some(index) = self.windows.iter().position(|w| w.common().parent == Some(parent)) {
+                            let mut window = self.windows.remove(index);
+                            window.close_viewport();
+                            window.destroy();
+                            closed.extend(window.get_window_id());
+                        }
+                    }
+                }
+
+                /// Find the window with the given role, see [NewWindowRequest::with_role]. Returns the window request id of the window.
+                pub fn window_by_role(&self, role: &str) -> Option<u32> {
+                    self.windows
+                        .iter()
+                        .find(|w| w.common().role.as_deref() == Some(role))
+                        .map(|w| w.common().request_id())
+                }
+
+                /// Returns true if the window, specified by the window request id, is minimized. None if the platform can't tell, or the window does not exist.
+                pub fn is_minimized(&self, id: u32) -> Option<bool> {
+                    self.find_window(id)?.is_minimized()
+                }
+
+                /// Returns true if the window, specified by the window request id, is maximized. None if the window does not exist.
+                pub fn is_maximized(&self, id: u32) -> Option<bool> {
+                    self.find_window(id)?.is_maximized()
+                }
+
+                /// Returns true if the window, specified by the window request id, is visible. None if the platform can't tell, or the window does not exist.
+                pub fn is_visible(&self, id: u32) -> Option<bool> {
+                    self.find_window(id)?.is_visible()
+                }
+
+                /// Set the minimum inner size of a window, specified by the window request id. None removes the minimum.
+                /// This works on windows that are already shown, if the window is currently smaller it is enlarged.
+                /// The opengl surface follows the size the window actually has, so it always matches the constrained size.
+                pub fn set_min_inner_size(
+                    &self,
+                    id: u32,
+                    size: Option<egui_multiwin::winit::dpi::Size>,
+                ) -> Result<(), egui_multiwin::multi_window::WindowError> {
+                    let window = self
+                        .find_window(id)
+                        .and_then(|w| w.window())
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    window.set_min_inner_size(size);
+                    Ok(())
+                }
+
+                /// Set the maximum inner size of a window, specified by the window request id. None removes the maximum.
+                /// This works on windows that are already shown, if the window is currently larger it is shrunk.
+                pub fn set_max_inner_size(
+                    &self,
+                    id: u32,
+                    size: Option<egui_multiwin::winit::dpi::Size>,
+                ) -> Result<(), egui_multiwin::multi_window::WindowError> {
+                    let window = self
+                        .find_window(id)
+                        .and_then(|w| w.window())
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    window.set_max_inner_size(size);
+                    Ok(())
+                }
+
+                /// Send a synthetic window event to a window, specified by the window request id. This is useful for scripted interaction and automated testing.
+                /// The event is processed just like a real event (including by egui) when the event loop handles its next event, and the window is redrawn afterwards.
+                pub fn post_window_event(
+                    &mut self,
+                    id: u32,
+                    event: winit::event::WindowEvent,
+                ) -> Result<(), egui_multiwin::multi_window::WindowError> {
+                    let window = self
+                        .find_window(id)
+                        .and_then(|w| w.window())
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    let window_id = window.id();
+                    // Requesting a redraw wakes the event loop, so the event is processed promptly
+                    window.request_redraw();
+                    self.posted_events.push((window_id, event));
+                    Ok(())
+                }
+
+                /// Bring a window, specified by the window request id, to the front and give it input focus. A minimized window is restored first.
+                pub fn focus_window(&self, id: u32) -> Result<(), egui_multiwin::multi_window::WindowError> {
+                    let window = self
+                        .find_window(id)
+                        .and_then(|w| w.window())
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    window.set_minimized(false);
+                    window.focus_window();
+                    Ok(())
+                }
+
+                /// The deferred viewports that have a window, created with `show_viewport_deferred`, along with the winit window id of each.
+                /// The window id is None while the window is suspended.
+                pub fn viewports(&self) -> impl Iterator<Item = (ViewportId, Option<winit::window::WindowId>)> + '_ {
+                    self.windows
+                        .iter()
+                        .filter_map(|w| w.viewport_id().map(|id| (id, w.get_window_id())))
+                }
+
+                /// Bring the window of a deferred viewport to the front and give it input focus. A minimized window is restored first.
+                pub fn focus_viewport(&self, id: ViewportId) -> Result<(), egui_multiwin::multi_window::WindowError> {
+                    let window = self
+                        .windows
+                        .iter()
+                        .find(|w| w.viewport_id() == Some(id))
+                        .and_then(|w| w.window())
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    window.set_minimized(false);
+                    window.focus_window();
+                    Ok(())
+                }
+
+                /// Request the attention of the user for a window, specified by the window request id, such as by flashing its taskbar entry.
+                /// This does nothing when the window is already focused, matching the behavior of the platforms. The request ends when the window is focused.
+                pub fn request_attention(
+                    &self,
+                    id: u32,
+                    kind: egui_multiwin::winit::window::UserAttentionType,
+                ) -> Result<(), egui_multiwin::multi_window::WindowError> {
+                    let window = self
+                        .find_window(id)
+                        .and_then(|w| w.window())
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    if !window.has_focus() {
+                        window.request_user_attention(Some(kind));
+                    }
+                    Ok(())
+                }
+
+                /// Change the level of a window, specified by the window request id, such as making it always on top.
+                /// This works on windows that are already shown. Nothing is done if the window does not exist.
+                pub fn set_window_level(
+                    &self,
+                    id: u32,
+                    level: egui_multiwin::winit::window::WindowLevel,
+                ) -> Result<(), egui_multiwin::multi_window::WindowError> {
+                    let window = self
+                        .find_window(id)
+                        .and_then(|w| w.window())
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    window.set_window_level(level);
+                    Ok(())
+                }
+
+                /// Change whether a window, specified by the window request id, receives mouse input. See [egui_multiwin::tracked_window::TrackedWindowOptions::cursor_hittest].
+                pub fn set_cursor_hittest(
+                    &self,
+                    id: u32,
+                    hittest: bool,
+                ) -> Result<(), egui_multiwin::multi_window::WindowError> {
+                    let window = self
+                        .find_window(id)
+                        .and_then(|w| w.window())
+                        .ok_or(egui_multiwin::multi_window::WindowError::NotFound)?;
+                    window
+                        .set_cursor_hittest(hittest)
+                        .map_err(|_| egui_multiwin::multi_window::WindowError::Unsupported)
+                }
+
+                /// Adds a new `TrackedWindow` to the `MultiWindow`, returning the id of the created window. When no window is marked as root, the first window added acts as the root window.
+                pub fn add<TE>(
+                    &mut self,
+                    window: NewWindowRequest,
+                    _c: &mut $common,
+                    event_loop: &egui_multiwin::winit::event_loop::EventLoopWindowTarget<TE>,
+                ) -> Result<winit::window::WindowId, DisplayCreationError> {
+                    let mut twc = TrackedWindowContainer::create::<TE>(
+                        window.window_state,
+                        window.viewportset,
+                        &window
+                            .viewport_id
+                            .unwrap_or(egui::viewport::ViewportId::ROOT),
+                        window.viewport_callback,
                         window.builder,
                         event_loop,
                         &window.options,
                         window.viewport,
                     )?;
-                    let w = twc.get_window_id();
-                    let mut table = egui_multiwin::multi_window::WINDOW_TABLE.lock().unwrap();
+                    twc.common_mut().initial_focus = window.initial_focus;
+                    twc.common_mut().set_request_id(window.id);
+                    twc.common_mut().role = window.role;
+                    // The parent must already exist, so parent links can never form a cycle
+                    twc.common_mut().parent = window.child_of.and_then(egui_multiwin::multi_window::get_window_id);
+                    if let Some(parent) = window.modal_for.and_then(egui_multiwin::multi_window::get_window_id) {
+                        twc.common_mut().modal_for = Some(parent);
+                        // Redraw the parent so it shows as blocked
+                        if let Some(w) = self.windows.iter().find(|w| w.get_window_id() == Some(parent)) {
+                            if let Some(window) = w.window() {
+                                window.request_redraw();
+                            }
+                        }
+                    }
+                    let wid = twc
+                        .get_window_id()
+                        .expect("a newly created window always has a window id");
+                    let w = Some(wid);
+                    let mut table = egui_multiwin::multi_window::WINDOW_TABLE.lock().unwrap_or_else(|e| e.into_inner());
                     if let Some(id) = table.get_mut(&window.id) {
                         *id = w;
                     }
+                    if self.first_window.is_none() && twc.get_window_data().is_some() {
+                        self.first_window = w;
+                    }
                     self.windows.push(twc);
-                    Ok(())
+                    Ok(wid)
+                }
+
+                /// Adds a window with the given role, unless a window with that role is already open. In that case the existing window is
+                /// focused instead, and the request is dropped. Returns the window request id of the window with the role.
+                pub fn add_or_focus<TE>(
+                    &mut self,
+                    mut window: NewWindowRequest,
+                    role: &str,
+                    c: &mut $common,
+                    event_loop: &egui_multiwin::winit::event_loop::EventLoopWindowTarget<TE>,
+                ) -> Result<u32, DisplayCreationError> {
+                    if let Some(id) = self.window_by_role(role) {
+                        let _e = self.focus_window(id);
+                        return Ok(id);
+                    }
+                    window.role = Some(role.to_string());
+                    let id = window.id;
+                    self.add(window, c, event_loop)?;
+                    Ok(id)
                 }
 
                 /// Process the given event for the applicable window(s)
@@ -929,40 +3285,140 @@ macro_rules! multi_window {
                     c: &mut $common,
                     event: &winit::event::Event<$event>,
                     event_loop_window_target: &winit::event_loop::EventLoopWindowTarget<$event>,
+                ) -> Vec<Option<ControlFlow>> {
+                    self.dispatch_window_events(c, event, None, event_loop_window_target)
+                }
+
+                /// Queue a message for a window, specified by the window request id. See [post_to_window].
+                pub fn post_to_window(&mut self, id: u32, msg: $event) {
+                    post_to_window(id, msg);
+                }
+
+                /// Process the given event for the applicable window(s). When target is specified, only that window receives the event, regardless of the event.
+                fn dispatch_window_events(
+                    &mut self,
+                    c: &mut $common,
+                    event: &winit::event::Event<$event>,
+                    target: Option<winit::window::WindowId>,
+                    event_loop_window_target: &winit::event_loop::EventLoopWindowTarget<$event>,
                 ) -> Vec<Option<ControlFlow>> {
                     let mut handled_windows = vec![];
                     let mut window_control_flow = vec![];
+                    // Parent windows to focus again after their modal dialog closes
+                    let mut refocus = vec![];
+                    // Windows that closed, their children are closed as well
+                    let mut closed = vec![];
+                    // Requests for windows with a role, added once all windows are back in the list
+                    let mut role_requests = vec![];
+
+                    // Windows with an open modal dialog do not get input
+                    let modal_parents: Vec<winit::window::WindowId> = self
+                        .windows
+                        .iter()
+                        .filter_map(|w| w.common().modal_for)
+                        .collect();
+
+                    if let winit::event::Event::WindowEvent { window_id, event } = event {
+                        if let winit::event::WindowEvent::Focused(true) = event {
+                            // Focusing a blocked window brings its modal dialogs to the front instead
+                            if modal_parents.contains(window_id) {
+                                for w in &self.windows {
+                                    if w.common().modal_for == Some(*window_id) {
+                                        if let Some(window) = w.window() {
+                                            window.focus_window();
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        self.input.update(*window_id, event);
+                        if let Some(parent) = super::tracked_window::immediate_viewport_event(*window_id, event) {
+                            if let Some(w) = self.windows.iter().find(|w| w.get_window_id() == Some(parent)) {
+                                if let Some(window) = w.window() {
+                                    window.request_redraw();
+                                }
+                            }
+                        }
+                    }
 
                     let mut root_window_exists = false;
+                    let mut root_window_marked = false;
                     for other in &self.windows {
                         if let Some(window) = other.get_window_data() {
                             if window.is_root() {
                                 root_window_exists = true;
+                                root_window_marked = true;
                             }
                         }
                     }
+                    // When no window is marked as root, the first window added acts as the root window
+                    if !root_window_marked && self.first_window.is_some() {
+                        root_window_exists = self
+                            .windows
+                            .iter()
+                            .any(|w| w.get_window_id() == self.first_window);
+                    }
+
+                    // Non-root windows are closed when there are no root windows, unless they keep the application alive by themselves
+                    let keep_non_root = root_window_exists
+                        || self.exit_policy == egui_multiwin::multi_window::ExitPolicy::WhenNoWindows;
 
                     while let Some(mut window) = self.windows.pop() {
-                        if window.is_event_for_window(event) {
+                        let for_window = match target {
+                            Some(target) => window.get_window_id() == Some(target),
+                            None => window.is_event_for_window(event),
+                        };
+                        if for_window {
+                            let blocked = window
+                                .get_window_id()
+                                .map(|id| modal_parents.contains(&id))
+                                .unwrap_or(false);
+                            window.common_mut().set_input_blocked(blocked);
+                            window.common_mut().set_power_saving(self.power_saving);
+                            let settings = EguiSettings {
+                                fonts: &self.fonts,
+                                reduced_motion: self.reduced_motion,
+                                image_loaders: self.image_loaders,
+                                debug: self.debug,
+                                visuals: self.visuals.as_ref(),
+                                tessellation_options: self.tessellation_options.as_ref(),
+                                proxy: self.proxy.as_ref(),
+                            };
                             let window_control = window.handle_event_outer(
                                 c,
                                 event,
                                 event_loop_window_target,
-                                root_window_exists,
-                                &self.fonts,
-                                &mut self.clipboard,
+                                keep_non_root,
+                                &settings,
+                                self.clipboard.as_mut(),
                             );
                             match window_control.requested_control_flow {
                                 None => {
                                     //println!("window requested exit. Instead of sending the exit for everyone, just get rid of this one.");
+                                    let is_root = window.get_window_data().map(|w| w.is_root()).unwrap_or(false)
+                                        || (!root_window_marked && window.get_window_id() == self.first_window);
+                                    let modal_for = window.common().modal_for;
+                                    let wid = window.get_window_id();
                                     if let Some(window) = window.get_window_data_mut() {
                                         if window.can_quit(c) {
+                                            if is_root
+                                                && self.exit_policy
+                                                    == egui_multiwin::multi_window::ExitPolicy::WhenAnyRootCloses
+                                            {
+                                                self.quit_requested.quit();
+                                            }
+                                            refocus.extend(modal_for);
+                                            closed.extend(wid);
                                             window_control_flow.push(None);
                                             continue;
                                         } else {
                                             window_control_flow.push(Some(ControlFlow::Wait));
                                         }
                                     } else {
+                                        if let Some(id) = window.close_viewport() {
+                                            c.viewport_closed(id);
+                                        }
+                                        closed.extend(wid);
                                         window_control_flow.push(None);
                                         continue;
                                     }
@@ -974,7 +3430,11 @@ macro_rules! multi_window {
                             }
 
                             for new_window_request in window_control.windows_to_create {
-                                let _e = self.add(new_window_request, c, event_loop_window_target);
+                                if new_window_request.role.is_some() {
+                                    role_requests.push(new_window_request);
+                                } else {
+                                    let _e = self.add(new_window_request, c, event_loop_window_target);
+                                }
                             }
                         }
                         handled_windows.push(window);
@@ -984,80 +3444,307 @@ macro_rules! multi_window {
                     handled_windows.reverse();
                     self.windows.append(&mut handled_windows);
 
+                    self.close_children(closed);
+
+                    for request in role_requests {
+                        if let Some(role) = request.role.clone() {
+                            let _e = self.add_or_focus(request, &role, c, event_loop_window_target);
+                        }
+                    }
+
+                    for parent in refocus {
+                        if let Some(w) = self.windows.iter().find(|w| w.get_window_id() == Some(parent)) {
+                            if let Some(window) = w.window() {
+                                window.focus_window();
+                                window.request_redraw();
+                            }
+                        }
+                    }
+
                     window_control_flow
                 }
 
-                /// Runs the event loop until all `TrackedWindow`s are closed.
-                pub fn run(
-                    mut self,
-                    event_loop: EventLoop<$event>,
-                    mut c: $common,
-                ) -> Result<(), EventLoopError> {
-                    event_loop.run(move |event, event_loop_window_target| {
-                        let c = &mut c;
-                        //println!("handling event {:?}", event);
-                        let window_try = if let winit::event::Event::UserEvent(uevent) = &event {
-                            uevent.window_id().is_some()
-                        } else {
-                            true
-                        };
-                        let window_control_flow = if window_try {
-                            self.do_window_events(c, &event, event_loop_window_target)
-                        } else {
-                            if let winit::event::Event::UserEvent(uevent) = event {
-                                for w in c.process_event(uevent) {
-                                    let _e = self.add(w, c, event_loop_window_target);
+                /// Process a single event from the event loop, for all windows.
+                fn process_loop_event(
+                    &mut self,
+                    c: &mut $common,
+                    event: winit::event::Event<$event>,
+                    event_loop_window_target: &winit::event_loop::EventLoopWindowTarget<$event>,
+                ) {
+                    //println!("handling event {:?}", event);
+                    for (window_id, event) in std::mem::take(&mut self.posted_events) {
+                        let _e = self.do_window_events(
+                            c,
+                            &winit::event::Event::WindowEvent { window_id, event },
+                            event_loop_window_target,
+                        );
+                    }
+                    if let Some(device_events) = self.device_events.take() {
+                        event_loop_window_target.listen_device_events(device_events);
+                    }
+                    if let Some(repaint) = self.repaint_queue.take() {
+                        for w in &self.windows {
+                            if repaint.all || repaint.ids.contains(&w.common().request_id()) {
+                                if let Some(window) = w.window() {
+                                    window.request_redraw();
                                 }
                             }
-                            vec![Some(ControlFlow::Poll)]
-                        };
+                        }
+                    }
+                    if let winit::event::Event::DeviceEvent { event, .. } = &event {
+                        c.device_event(event);
+                    }
+                    if let winit::event::Event::LoopExiting = &event {
+                        c.on_exit();
+                    }
+                    let about_to_wait = matches!(event, winit::event::Event::AboutToWait);
+                    let window_try = if let winit::event::Event::UserEvent(uevent) = &event {
+                        // Events for windows that do not exist are handled as events for no window
+                        uevent
+                            .window_id()
+                            .map(|wid| self.windows.iter().any(|w| w.get_window_id() == Some(wid)))
+                            .unwrap_or(false)
+                    } else {
+                        true
+                    };
+                    let mut quit = false;
+                    let mut window_control_flow = if window_try {
+                        self.do_window_events(c, &event, event_loop_window_target)
+                    } else {
+                        if let winit::event::Event::UserEvent(uevent) = event {
+                            for w in c.process_event(uevent) {
+                                let _e = self.add(w, c, event_loop_window_target);
+                            }
+                            quit = c.should_quit();
+                        }
+                        vec![Some(ControlFlow::Poll)]
+                    };
+
+                    for (id, state) in REPLACEMENTS.with(|r| std::mem::take(&mut *r.borrow_mut())) {
+                        let _e = self.replace_window_state(id, state);
+                    }
 
-                        let mut flow = Some(event_loop_window_target.control_flow());
-
-                        // If any window requested polling, we should poll.
-                        // Precedence: Poll > WaitUntil(smallest) > Wait.
-                        if flow.is_none() {
-                        } else if let Some(flow) = &mut flow {
-                            *flow = ControlFlow::Wait;
-                            for flow_request in window_control_flow {
-                                if let Some(flow_request) = flow_request {
-                                    match flow_request {
-                                        ControlFlow::Poll => {
-                                            *flow = ControlFlow::Poll;
+                    for (id, builder) in super::tracked_window::take_viewport_builders() {
+                        if let Some(w) = self
+                            .windows
+                            .iter_mut()
+                            .find(|w| w.viewport_id() == Some(id))
+                        {
+                            w.update_viewport_builder(builder);
+                        }
+                    }
+
+                    if about_to_wait {
+                        if let Some(when) = c.idle() {
+                            window_control_flow.push(Some(ControlFlow::WaitUntil(when)));
+                        }
+                    }
+
+                    for (id, msg) in MAILBOX.with(|m| std::mem::take(&mut *m.borrow_mut())) {
+                        if let Some(window_id) = egui_multiwin::multi_window::get_window_id(id) {
+                            window_control_flow.extend(self.dispatch_window_events(
+                                c,
+                                &winit::event::Event::UserEvent(msg),
+                                Some(window_id),
+                                event_loop_window_target,
+                            ));
+                        }
+                    }
+
+                    if self.quit_requested.take() {
+                        let mut remaining = Vec::new();
+                        for mut window in self.windows.drain(..) {
+                            let allowed = window
+                                .get_window_data_mut()
+                                .map(|w| w.can_quit(c))
+                                .unwrap_or(true);
+                            if allowed {
+                                window.destroy();
+                            } else {
+                                remaining.push(window);
+                            }
+                        }
+                        self.windows = remaining;
+                        // An explicit quit does not wait for the exit grace period
+                        quit |= self.windows.is_empty();
+                    }
+
+                    let mut flow = Some(event_loop_window_target.control_flow());
+
+                    // If any window requested polling, we should poll.
+                    // Precedence: Poll > WaitUntil(smallest) > Wait.
+                    if flow.is_none() {
+                    } else if let Some(flow) = &mut flow {
+                        *flow = ControlFlow::Wait;
+                        for flow_request in window_control_flow {
+                            if let Some(flow_request) = flow_request {
+                                match flow_request {
+                                    ControlFlow::Poll => {
+                                        *flow = ControlFlow::Poll;
+                                    }
+                                    ControlFlow::Wait => (), // do nothing, if untouched it will be wait
+                                    ControlFlow::WaitUntil(when_new) => {
+                                        if let ControlFlow::Poll = *flow {
+                                            continue; // Polling takes precedence, so ignore this.
                                         }
-                                        ControlFlow::Wait => (), // do nothing, if untouched it will be wait
-                                        ControlFlow::WaitUntil(when_new) => {
-                                            if let ControlFlow::Poll = *flow {
-                                                continue; // Polling takes precedence, so ignore this.
-                                            }
 
-                                            // The current flow is already WaitUntil. If this one is sooner, use it instead.
-                                            if let ControlFlow::WaitUntil(when_current) = *flow {
-                                                if when_new < when_current {
-                                                    *flow = ControlFlow::WaitUntil(when_new);
-                                                }
-                                            } else {
-                                                // The current flow is lower precedence, so replace it with this.
+                                        // The current flow is already WaitUntil. If this one is sooner, use it instead.
+                                        if let ControlFlow::WaitUntil(when_current) = *flow {
+                                            if when_new < when_current {
                                                 *flow = ControlFlow::WaitUntil(when_new);
                                             }
+                                        } else {
+                                            // The current flow is lower precedence, so replace it with this.
+                                            *flow = ControlFlow::WaitUntil(when_new);
                                         }
                                     }
                                 }
                             }
                         }
+                    }
 
-                        if self.windows.is_empty() {
-                            //println!("no more windows running, exiting event loop.");
+                    if self.windows.is_empty() {
+                        //println!("no more windows running, exiting event loop.");
+                        if let Some(grace) = self.exit_grace_period {
+                            let now = std::time::Instant::now();
+                            let deadline = *self.exit_deadline.get_or_insert(now + grace);
+                            if now >= deadline {
+                                flow = None;
+                            } else {
+                                flow = Some(ControlFlow::WaitUntil(deadline));
+                            }
+                        } else {
                             flow = None;
                         }
+                    } else {
+                        self.exit_deadline = None;
+                    }
 
-                        if let Some(flow) = flow {
-                            event_loop_window_target.set_control_flow(flow);
-                        } else {
-                            event_loop_window_target.exit();
-                        }
+                    if quit {
+                        flow = None;
+                    }
+
+                    if let Some(flow) = flow {
+                        event_loop_window_target.set_control_flow(flow);
+                    } else {
+                        event_loop_window_target.exit();
+                    }
+                }
+
+                /// Runs the event loop until all `TrackedWindow`s are closed.
+                pub fn run(
+                    mut self,
+                    event_loop: EventLoop<$event>,
+                    mut c: $common,
+                ) -> Result<(), EventLoopError> {
+                    self.proxy = Some(event_loop.create_proxy());
+                    event_loop.run(move |event, event_loop_window_target| {
+                        self.process_loop_event(&mut c, event, event_loop_window_target);
                     })
                 }
+
+                /// Process pending events and then return control to the caller, instead of running the event loop until the application exits.
+                /// This allows embedding the event loop in a larger application, or driving windows for a number of iterations in a test.
+                /// timeout specifies how long to wait for new events, None waits indefinitely, and a zero duration returns immediately after processing pending events.
+                /// Returns the control flow requested by the windows and true once the event loop has exited and `on_exit` has run.
+                /// During the exit grace period all windows can be closed while the event loop has not exited yet.
+                /// See the winit documentation of `EventLoopExtPumpEvents` for platform specific caveats.
+                pub fn run_return(
+                    &mut self,
+                    event_loop: &mut EventLoop<$event>,
+                    c: &mut $common,
+                    timeout: Option<std::time::Duration>,
+                ) -> (ControlFlow, bool) {
+                    use egui_multiwin::winit::platform::pump_events::{EventLoopExtPumpEvents, PumpStatus};
+                    let mut flow = ControlFlow::Wait;
+                    if self.proxy.is_none() {
+                        self.proxy = Some(event_loop.create_proxy());
+                    }
+                    let status = event_loop.pump_events(timeout, |event, event_loop_window_target| {
+                        self.process_loop_event(c, event, event_loop_window_target);
+                        flow = event_loop_window_target.control_flow();
+                    });
+                    (flow, matches!(status, PumpStatus::Exit(_)))
+                }
+            }
+
+            /// A fluent builder for a [NewWindowRequest], created with [NewWindowRequest::builder]
+            pub struct NewWindowRequestBuilder {
+                /// The window data
+                window_state: $window,
+                /// The builder for the winit window
+                builder: egui_multiwin::winit::window::WindowBuilder,
+                /// The options for the window
+                options: TrackedWindowOptions,
+                /// The window request id, a new id is created when this is None
+                id: Option<u32>,
+            }
+
+            impl NewWindowRequestBuilder {
+                /// Set the title of the window
+                pub fn title(mut self, title: impl Into<String>) -> Self {
+                    self.builder = self.builder.with_title(title);
+                    self
+                }
+
+                /// Set the inner size of the window, in logical pixels
+                pub fn inner_size(mut self, width: f64, height: f64) -> Self {
+                    self.builder = self
+                        .builder
+                        .with_inner_size(egui_multiwin::winit::dpi::LogicalSize::new(width, height));
+                    self
+                }
+
+                /// Set whether the window can be resized by the user
+                pub fn resizable(mut self, resizable: bool) -> Self {
+                    self.builder = self.builder.with_resizable(resizable);
+                    self
+                }
+
+                /// Enable or disable vsync for the window
+                pub fn vsync(mut self, vsync: bool) -> Self {
+                    self.options.present_mode = Some(if vsync {
+                        egui_multiwin::tracked_window::PresentMode::Fifo
+                    } else {
+                        egui_multiwin::tracked_window::PresentMode::Immediate
+                    });
+                    self
+                }
+
+                /// Set the options of the window
+                pub fn options(mut self, options: TrackedWindowOptions) -> Self {
+                    self.options = options;
+                    self
+                }
+
+                /// Use a specific window request id instead of a new one
+                pub fn id(mut self, id: u32) -> Self {
+                    self.id = Some(id);
+                    self
+                }
+
+                /// Modify the underlying winit window builder, for settings not covered by this builder
+                pub fn with_window_builder(
+                    mut self,
+                    f: impl FnOnce(egui_multiwin::winit::window::WindowBuilder) -> egui_multiwin::winit::window::WindowBuilder,
+                ) -> Self {
+                    self.builder = f(self.builder);
+                    self
+                }
+
+                /// Create the window request. Returns [egui_multiwin::multi_window::WindowError::NoIdsLeft] when no id was set and every id is in use.
+                pub fn build(self) -> Result<NewWindowRequest, egui_multiwin::multi_window::WindowError> {
+                    let id = match self.id {
+                        Some(id) => id,
+                        None => egui_multiwin::multi_window::new_id()?,
+                    };
+                    Ok(NewWindowRequest::new(
+                        self.window_state,
+                        self.builder,
+                        self.options,
+                        id,
+                    ))
+                }
             }
 
             /// A struct defining how a new window is to be created.
@@ -1078,6 +3765,14 @@ macro_rules! multi_window {
                 viewportset: Arc<Mutex<ViewportIdSet>>,
                 /// The viewport callback
                 viewport_callback: Option<std::sync::Arc<DeferredViewportUiCallback>>,
+                /// The widget to focus when the window is first shown
+                initial_focus: Option<egui::Id>,
+                /// The window request id of the parent window when the window is a modal dialog
+                modal_for: Option<u32>,
+                /// The window request id of the parent window, the window is closed along with its parent
+                child_of: Option<u32>,
+                /// The application defined role of the window
+                role: Option<String>,
             }
 
             impl NewWindowRequest {
@@ -1097,6 +3792,42 @@ macro_rules! multi_window {
                         viewport_id: None,
                         viewportset: Arc::new(Mutex::new(egui::viewport::ViewportIdSet::default())),
                         viewport_callback: None,
+                        initial_focus: None,
+                        modal_for: None,
+                        child_of: None,
+                        role: None,
+                    }
+                }
+
+                /// Create a new root window from a boxed window, for applications that use `Box<dyn TrackedWindow>` as the window type
+                /// instead of an enum. The box should be a `Box<dyn TrackedWindow>`.
+                pub fn new_boxed<W>(
+                    window_state: Box<W>,
+                    builder: egui_multiwin::winit::window::WindowBuilder,
+                    options: TrackedWindowOptions,
+                    id: u32,
+                ) -> Self
+                where
+                    W: TrackedWindow + ?Sized,
+                    Box<W>: Into<$window>,
+                {
+                    Self::new(window_state.into(), builder, options, id)
+                }
+
+                /// Start building a window request with a fluent builder. The id defaults to a fresh id from `new_id`, and the options to their defaults.
+                /// ```ignore
+                /// let request = NewWindowRequest::builder(window)
+                ///     .title("My window")
+                ///     .inner_size(400.0, 200.0)
+                ///     .resizable(false)
+                ///     .build();
+                /// ```
+                pub fn builder(window_state: $window) -> NewWindowRequestBuilder {
+                    NewWindowRequestBuilder {
+                        window_state,
+                        builder: egui_multiwin::winit::window::WindowBuilder::new(),
+                        options: TrackedWindowOptions::default(),
+                        id: None,
                     }
                 }
 
@@ -1119,9 +3850,281 @@ macro_rules! multi_window {
                         viewport_id: Some(vp_id),
                         viewport_callback: vpcb,
                         viewportset,
+                        initial_focus: None,
+                        modal_for: None,
+                        child_of: None,
+                        role: None,
                     }
                 }
+
+                /// Set the icon of the window from rgba data. The data must be 4 bytes per pixel, with rows tightly packed (width * 4 bytes per row), for a total of width * height * 4 bytes.
+                pub fn with_icon(
+                    mut self,
+                    rgba: Vec<u8>,
+                    width: u32,
+                    height: u32,
+                ) -> Result<Self, egui_multiwin::winit::window::BadIcon> {
+                    let icon = egui_multiwin::winit::window::Icon::from_rgba(rgba, width, height)?;
+                    self.builder = self.builder.with_window_icon(Some(icon));
+                    Ok(self)
+                }
+
+                /// Put the window in an opengl share group, see [TrackedWindowOptions::share_group]
+                pub fn with_share_group(mut self, group: egui_multiwin::tracked_window::GroupId) -> Self {
+                    self.options.share_group = Some(group);
+                    self
+                }
+
+                /// Request that the widget with the given id gets keyboard focus once the window is visible and focused.
+                /// The focus request is held back until the window has focus, so it is not lost on the first frame.
+                pub fn with_initial_focus(mut self, id: egui::Id) -> Self {
+                    self.initial_focus = Some(id);
+                    self
+                }
+
+                /// Make the window a modal dialog of the window with the given window request id. While the dialog is open,
+                /// the parent is still redrawn but dimmed, and egui does not receive keyboard, mouse, or touch input for it.
+                /// Focusing the parent brings the dialog to the front, and the parent is focused again once the dialog closes.
+                pub fn modal_for(mut self, parent_id: u32) -> Self {
+                    self.modal_for = Some(parent_id);
+                    self
+                }
+
+                /// Make the window a child of the window with the given window request id. Closing the parent also closes all of its
+                /// children, and their children in turn. Closing a child does not affect the parent. The parent must already be open
+                /// when the window is added, otherwise the window has no parent.
+                pub fn child_of(mut self, parent_id: u32) -> Self {
+                    self.child_of = Some(parent_id);
+                    self
+                }
+
+                /// Give the window an application defined role, such as "settings". Unlike the window request id, the role is chosen by
+                /// the application, so it can be used to find an open window with [MultiWindow::window_by_role]. Windows with a role are
+                /// single instance when requested from a window, the existing window is focused instead, see [MultiWindow::add_or_focus].
+                pub fn with_role(mut self, role: impl Into<String>) -> Self {
+                    self.role = Some(role.into());
+                    self
+                }
+
+                /// Position the window so it is centered on the given monitor. The size of the window is taken from the builder, so set the size first.
+                /// If the builder has no size, the winit default of 800x600 logical pixels is assumed.
+                pub fn centered_on(mut self, monitor: egui_multiwin::winit::monitor::MonitorHandle) -> Self {
+                    let scale = monitor.scale_factor();
+                    let size: egui_multiwin::winit::dpi::PhysicalSize<i32> = self
+                        .builder
+                        .window_attributes()
+                        .inner_size
+                        .unwrap_or(egui_multiwin::winit::dpi::LogicalSize::new(800.0, 600.0).into())
+                        .to_physical(scale);
+                    let mpos = monitor.position();
+                    let msize = monitor.size();
+                    let x = mpos.x + (msize.width as i32 - size.width) / 2;
+                    let y = mpos.y + (msize.height as i32 - size.height) / 2;
+                    self.builder = self
+                        .builder
+                        .with_position(egui_multiwin::winit::dpi::PhysicalPosition::new(x, y));
+                    self
+                }
+
+                /// Apply a saved position, size, and maximized/fullscreen state to the window
+                pub fn with_geometry(mut self, geom: &egui_multiwin::multi_window::WindowGeometry) -> Self {
+                    self.builder = geom.apply(self.builder);
+                    self
+                }
             }
         }
     };
 }
+
+/// Declares the enum of all windows of an application and generates everything around it in one place. This expands to the enum with
+/// the enum_dispatch wiring for `TrackedWindow`, the [`window_variants`](macro.window_variants.html) impls, and a module named
+/// `egui_multiwin_dynamic` containing the [`tracked_window`](macro.tracked_window.html) and [`multi_window`](macro.multi_window.html) modules.
+/// The common data structure and the event type should be given with absolute paths (starting with `crate::`).
+/// ```ignore
+/// egui_multiwin::window_enum! {
+///     common: crate::AppCommon,
+///     event: egui_multiwin::NoEvent,
+///     /// The windows for the program
+///     pub enum MyWindows {
+///         /// The root window
+///         Root(root::RootWindow),
+///         /// A popup window
+///         Popup(popup_window::PopupWindow),
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! window_enum {
+    (
+        common: $common:ty,
+        event: $event:ty,
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$vmeta:meta])* $variant:ident($wtype:ty)),* $(,)?
+        }
+    ) => {
+        /// Macro generated code
+        pub mod egui_multiwin_dynamic {
+            egui_multiwin::tracked_window!($common, $event, super::super::$name);
+            egui_multiwin::multi_window!($common, $event, super::super::$name);
+        }
+
+        /// The enum of all windows, kept in its own module so the signatures copied by enum_dispatch resolve
+        mod __window_enum {
+            #[allow(unused_imports)]
+            use super::*;
+            #[allow(unused_imports)]
+            use super::egui_multiwin_dynamic::tracked_window::{RedrawContext, RedrawResponse, TrackedWindow};
+            #[allow(unused_imports)]
+            use std::sync::Arc;
+
+            $(#[$meta])*
+            #[egui_multiwin::enum_dispatch::enum_dispatch(TrackedWindow)]
+            pub enum $name {
+                $($(#[$vmeta])* $variant($wtype)),*
+            }
+
+            egui_multiwin::window_variants!($name, $($variant($wtype)),*);
+        }
+
+        $vis use __window_enum::$name;
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    //! Tests for the parts of the multi window support that do not need a display
+
+    use super::*;
+    use winit::event::{DeviceId, ElementState, MouseButton, WindowEvent};
+    use winit::keyboard::ModifiersState;
+
+    /// A mouse button event for the tests
+    fn mouse(state: ElementState) -> WindowEvent {
+        WindowEvent::MouseInput {
+            device_id: unsafe { DeviceId::dummy() },
+            state,
+            button: MouseButton::Left,
+        }
+    }
+
+    /// Held modifiers and buttons are tracked, and cleared when focus moves to another window
+    #[test]
+    fn input_snapshot_clears_on_focus_change() {
+        let a = WindowId::from(1);
+        let b = WindowId::from(2);
+        let mut snapshot = InputSnapshot::default();
+        snapshot.update(a, &WindowEvent::Focused(true));
+        snapshot.update(a, &WindowEvent::ModifiersChanged(ModifiersState::SHIFT.into()));
+        snapshot.update(a, &mouse(ElementState::Pressed));
+        assert_eq!(snapshot.focused_window, Some(a));
+        assert_eq!(snapshot.modifiers, ModifiersState::SHIFT);
+        assert!(snapshot.mouse_buttons.contains(&MouseButton::Left));
+
+        snapshot.update(b, &WindowEvent::Focused(true));
+        assert_eq!(snapshot.focused_window, Some(b));
+        assert_eq!(snapshot.modifiers, ModifiersState::empty());
+        assert!(snapshot.mouse_buttons.is_empty());
+    }
+
+    /// Releasing a button removes it, and losing focus or destroying the focused window clears the snapshot
+    #[test]
+    fn input_snapshot_release_and_destroy() {
+        let a = WindowId::from(1);
+        let b = WindowId::from(2);
+        let mut snapshot = InputSnapshot::default();
+        snapshot.update(a, &WindowEvent::Focused(true));
+        snapshot.update(a, &mouse(ElementState::Pressed));
+        snapshot.update(a, &mouse(ElementState::Released));
+        assert!(snapshot.mouse_buttons.is_empty());
+
+        // Another window losing focus or being destroyed does not affect the focused window
+        snapshot.update(a, &mouse(ElementState::Pressed));
+        snapshot.update(b, &WindowEvent::Focused(false));
+        snapshot.update(b, &WindowEvent::Destroyed);
+        assert_eq!(snapshot.focused_window, Some(a));
+        assert!(!snapshot.mouse_buttons.is_empty());
+
+        snapshot.update(a, &WindowEvent::Destroyed);
+        assert_eq!(snapshot.focused_window, None);
+        assert!(snapshot.mouse_buttons.is_empty());
+    }
+
+    /// Requests are combined until they are taken, and only the first request reports an empty queue
+    #[test]
+    fn repaint_queue_combines_requests() {
+        let queue = RepaintQueue::default();
+        assert!(queue.take().is_none());
+        assert!(queue.push(|r| {
+            r.ids.insert(1);
+        }));
+        assert!(!queue.push(|r| {
+            r.ids.insert(2);
+        }));
+        let requests = queue.take().expect("requests were made");
+        assert!(!requests.all);
+        assert_eq!(requests.ids, vec![1, 2].into_iter().collect());
+        assert!(queue.take().is_none());
+
+        assert!(queue.push(|r| r.all = true));
+        assert!(queue.take().expect("a request was made").all);
+    }
+
+    /// A window closes when its redraw or custom_event function returns quit, and non-root windows close when no root window is left
+    #[test]
+    fn quit_closes_window() {
+        let wait = Some(winit::event_loop::ControlFlow::Wait);
+        assert_eq!(window_control_flow(wait, false, Some(true), true), wait);
+        assert_eq!(window_control_flow(wait, true, Some(true), true), None);
+        assert_eq!(window_control_flow(wait, true, Some(false), true), None);
+        assert_eq!(window_control_flow(wait, true, None, true), None);
+        assert_eq!(window_control_flow(wait, false, Some(false), true), wait);
+        assert_eq!(window_control_flow(wait, false, Some(false), false), None);
+        assert_eq!(window_control_flow(wait, false, Some(true), false), wait);
+        assert_eq!(window_control_flow(wait, false, None, false), wait);
+    }
+
+    /// A clone of a queue shares the requests, like the queue of a MultiWindow and its repaint signals
+    #[test]
+    fn repaint_queue_clones_share_requests() {
+        let queue = RepaintQueue::default();
+        let signal_side = queue.clone();
+        signal_side.push(|r| {
+            r.ids.insert(7);
+        });
+        let requests = queue.take().expect("the request is shared");
+        assert!(requests.ids.contains(&7));
+        assert!(signal_side.take().is_none());
+    }
+
+    /// A layout snapshot survives being saved and loaded
+    #[cfg(feature = "serde")]
+    #[test]
+    fn layout_snapshot_round_trip() {
+        let snapshot = LayoutSnapshot {
+            windows: vec![
+                LayoutSnapshotWindow {
+                    role: "main".to_string(),
+                    geometry: WindowGeometry {
+                        position: Some((10, -20)),
+                        inner_size: (800, 600),
+                        maximized: false,
+                        fullscreen: false,
+                    },
+                },
+                LayoutSnapshotWindow {
+                    role: "tools".to_string(),
+                    geometry: WindowGeometry {
+                        position: None,
+                        inner_size: (200, 400),
+                        maximized: true,
+                        fullscreen: false,
+                    },
+                },
+            ],
+        };
+        let saved = serde_json::to_string(&snapshot).unwrap();
+        let loaded: LayoutSnapshot = serde_json::from_str(&saved).unwrap();
+        assert_eq!(loaded, snapshot);
+    }
+}
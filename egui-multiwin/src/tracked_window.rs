@@ -3,6 +3,7 @@
 use std::num::NonZeroU32;
 
 use egui::NumExt;
+use egui_glow::glow;
 use glutin::context::{NotCurrentContext, PossiblyCurrentContext};
 use glutin::prelude::GlDisplay;
 use glutin::prelude::{NotCurrentGlContext, PossiblyCurrentGlContext};
@@ -25,6 +26,21 @@ pub struct ContextHolder<T> {
     options: TrackedWindowOptions,
     /// The last control flow of the window
     pub control_flow: Option<ControlFlow>,
+    /// Set when the opengl context has been lost, the context needs to be recreated before it can be used again
+    pub context_lost: bool,
+    /// True when the opengl config of the window supports sRGB framebuffers
+    srgb_capable: bool,
+    /// The opengl share group the context shares objects with, kept alive by the windows of the group
+    share_group: Option<std::rc::Rc<ShareGroup>>,
+}
+
+/// The context that the contexts of the windows in an opengl share group share objects with, and the display they are all created on.
+/// Every window of the group holds the share group, so it is destroyed when the last window of the group is closed.
+pub struct ShareGroup {
+    /// The display the contexts of the group are created on
+    pub display: glutin::display::Display,
+    /// The context that the contexts of the group share objects with
+    pub context: NotCurrentContext,
 }
 
 impl<T> ContextHolder<T> {
@@ -43,30 +59,90 @@ impl<T> ContextHolder<T> {
             display,
             options,
             control_flow: Some(ControlFlow::Poll),
+            context_lost: false,
+            srgb_capable: false,
+            share_group: None,
         }
     }
+
+    /// Record whether the opengl config of the window supports sRGB framebuffers
+    pub fn with_srgb_capable(mut self, capable: bool) -> Self {
+        self.srgb_capable = capable;
+        self
+    }
+
+    /// Record the opengl share group that the context shares objects with
+    pub fn with_share_group(mut self, group: Option<std::rc::Rc<ShareGroup>>) -> Self {
+        self.share_group = group;
+        self
+    }
+
+    /// Returns true when the framebuffer of the window should be sRGB, because the srgb option is set and the config supports it
+    pub fn srgb(&self) -> bool {
+        self.options.srgb && self.srgb_capable
+    }
 }
 impl<T> ContextHolder<T> {
     /// Get the window handle
     pub fn window(&self) -> &winit::window::Window {
         &self.window
     }
+
+    /// Get the options the window was created with
+    pub fn options(&self) -> &TrackedWindowOptions {
+        &self.options
+    }
+
+    /// Destroy the opengl surface and context, keeping only the window
+    pub fn into_window(self) -> winit::window::Window {
+        self.window
+    }
 }
 
 impl ContextHolder<PossiblyCurrentContext> {
-    /// Call swap_buffers. linux targets have vsync specifically disabled because it causes problems with hidden windows.
-    pub fn swap_buffers(&self) -> glutin::error::Result<()> {
-        if self.options.vsync {
-            let _e = self.ws.set_swap_interval(
-                &self.context,
-                glutin::surface::SwapInterval::Wait(NonZeroU32::MIN),
-            );
-        } else {
-            let _e = self
-                .ws
-                .set_swap_interval(&self.context, glutin::surface::SwapInterval::DontWait);
+    /// Call swap_buffers. The swap interval (vsync) is set once, when the context first becomes current.
+    /// Buffers are not swapped for windows that are known to be hidden, because waiting for vsync on a hidden window can block
+    /// indefinitely on some platforms (notably linux). This allows vsync to be used on all platforms.
+    /// A lost context is recorded in context_lost.
+    pub fn swap_buffers(&mut self) -> glutin::error::Result<()> {
+        if self.window.is_visible() == Some(false) {
+            return Ok(());
+        }
+        self.swap()
+    }
+
+    /// Swap buffers even when the window is hidden, without waiting for vsync. This presents the first frame of a window
+    /// that is created hidden, before the window is shown. A lost context is recorded in context_lost.
+    pub fn swap_buffers_hidden(&mut self) -> glutin::error::Result<()> {
+        let _e = self
+            .ws
+            .set_swap_interval(&self.context, glutin::surface::SwapInterval::DontWait);
+        let r = self.swap();
+        let _e = self.set_swap_interval();
+        r
+    }
+
+    /// Swap the buffers of the surface, recording a lost context
+    fn swap(&mut self) -> glutin::error::Result<()> {
+        let r = self.ws.swap_buffers(&self.context);
+        if let Err(e) = &r {
+            if e.error_kind() == glutin::error::ErrorKind::ContextLost {
+                self.context_lost = true;
+            }
         }
-        self.ws.swap_buffers(&self.context)
+        r
+    }
+
+    /// Set the swap interval of the surface according to the present mode of the window
+    fn set_swap_interval(&self) -> glutin::error::Result<()> {
+        let interval = match self.options.present_mode() {
+            PresentMode::Immediate => glutin::surface::SwapInterval::DontWait,
+            PresentMode::Fifo | PresentMode::Adaptive => {
+                glutin::surface::SwapInterval::Wait(NonZeroU32::MIN)
+            }
+            PresentMode::Interval(i) => glutin::surface::SwapInterval::Wait(i),
+        };
+        self.ws.set_swap_interval(&self.context, interval)
     }
 
     /// Resize the window to the specified size. The size cannot be zero in either dimension.
@@ -85,6 +161,11 @@ impl ContextHolder<PossiblyCurrentContext> {
         self.context.make_current(&self.ws)
     }
 
+    /// Returns true if the context is known to be lost, as recorded by swap_buffers
+    pub fn is_context_lost(&self) -> bool {
+        self.context_lost
+    }
+
     /// convenience function to call get_proc_address on the display of this struct
     pub fn get_proc_address(&self, s: &str) -> *const std::ffi::c_void {
         let cs: *const std::ffi::c_char = s.as_ptr().cast();
@@ -98,7 +179,17 @@ impl ContextHolder<NotCurrentContext> {
     pub fn make_current(
         self,
     ) -> Result<ContextHolder<PossiblyCurrentContext>, glutin::error::Error> {
-        let c = self.context.make_current(&self.ws).unwrap();
+        self.try_make_current().map_err(|(_, e)| e)
+    }
+
+    /// Transforms a not current context into a possibly current context, giving the window back on failure
+    pub fn try_make_current(
+        self,
+    ) -> Result<ContextHolder<PossiblyCurrentContext>, (Box<winit::window::Window>, glutin::error::Error)> {
+        let c = match self.context.make_current(&self.ws) {
+            Ok(c) => c,
+            Err(e) => return Err((Box::new(self.window), e)),
+        };
         let s = ContextHolder::<PossiblyCurrentContext> {
             context: c,
             window: self.window,
@@ -106,20 +197,571 @@ impl ContextHolder<NotCurrentContext> {
             display: self.display,
             options: self.options,
             control_flow: self.control_flow,
+            context_lost: false,
+            srgb_capable: self.srgb_capable,
+            share_group: self.share_group,
         };
+        let _e = s.set_swap_interval();
         Ok(s)
     }
 }
 
+/// Identifies a group of windows. Used for windows that share the same egui style (see the `share_style_group` function of the `TrackedWindow` trait),
+/// and for windows that share opengl objects (see [TrackedWindowOptions::share_group]).
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct GroupId(pub u32);
+
+/// How finished frames are presented to the screen, trading latency against tearing.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PresentMode {
+    /// Present immediately, without waiting for vertical blank. Lowest latency, but may tear.
+    Immediate,
+    /// Wait for every vertical blank (vsync).
+    Fifo,
+    /// Adaptive vsync, only tearing when a frame is late. glutin does not expose adaptive swap control, so this currently behaves like [PresentMode::Fifo].
+    Adaptive,
+    /// Wait for the specified number of vertical blanks between frames.
+    Interval(NonZeroU32),
+}
+
+/// The opengl api used to create the display of a window. Not every api is available on every platform.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GlApiPreference {
+    /// EGL, available on windows (with a library such as ANGLE) and on unix platforms other than macos
+    Egl,
+    /// GLX, available on unix platforms with x11
+    Glx,
+    /// WGL, available on windows
+    Wgl,
+    /// CGL, available on macos
+    Cgl,
+    /// EGL, falling back to GLX
+    EglThenGlx,
+    /// GLX, falling back to EGL
+    GlxThenEgl,
+    /// EGL, falling back to WGL
+    EglThenWgl,
+    /// WGL, falling back to EGL
+    WglThenEgl,
+}
+
+impl GlApiPreference {
+    /// Get the glutin display api preference for a window. Returns None when the api is not available on the current platform.
+    pub fn display_api(
+        self,
+        rwh: raw_window_handle_5::RawWindowHandle,
+    ) -> Option<glutin::display::DisplayApiPreference> {
+        use glutin::display::DisplayApiPreference as P;
+        let _ = rwh;
+        match self {
+            #[cfg(not(any(target_os = "macos", target_os = "ios")))]
+            Self::Egl => Some(P::Egl),
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ))]
+            Self::Glx => Some(P::Glx(Box::new(
+                winit::platform::x11::register_xlib_error_hook,
+            ))),
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ))]
+            Self::EglThenGlx => Some(P::EglThenGlx(Box::new(
+                winit::platform::x11::register_xlib_error_hook,
+            ))),
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ))]
+            Self::GlxThenEgl => Some(P::GlxThenEgl(Box::new(
+                winit::platform::x11::register_xlib_error_hook,
+            ))),
+            #[cfg(target_os = "windows")]
+            Self::Wgl => Some(P::Wgl(Some(rwh))),
+            #[cfg(target_os = "windows")]
+            Self::EglThenWgl => Some(P::EglThenWgl(Some(rwh))),
+            #[cfg(target_os = "windows")]
+            Self::WglThenEgl => Some(P::WglThenEgl(Some(rwh))),
+            #[cfg(target_os = "macos")]
+            Self::Cgl => Some(P::Cgl),
+            _ => None,
+        }
+    }
+}
+
+/// The kind of opengl context to create for a window
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum GlContextApi {
+    /// Desktop opengl
+    OpenGl,
+    /// OpenGL ES, with an optional major and minor version. Needed on some embedded systems and with ANGLE.
+    Gles(Option<(u8, u8)>),
+}
+
+impl GlContextApi {
+    /// Get the glutin context api
+    pub fn context_api(self) -> glutin::context::ContextApi {
+        match self {
+            Self::OpenGl => glutin::context::ContextApi::OpenGl(None),
+            Self::Gles(version) => glutin::context::ContextApi::Gles(
+                version.map(|(major, minor)| glutin::context::Version::new(major, minor)),
+            ),
+        }
+    }
+}
+
 /// The options for a window.
 #[derive(Copy, Clone)]
 pub struct TrackedWindowOptions {
-    /// Should the window be vsynced. Check github issues to see if this property actually does what it is supposed to.
+    /// Should the window be vsynced. Only used when present_mode is None, where true means [PresentMode::Fifo] and false means [PresentMode::Immediate].
+    #[deprecated(note = "use present_mode instead")]
     pub vsync: bool,
+    /// How frames are presented. The swap interval is applied once when the opengl context is first made current.
+    /// Hidden windows do not swap buffers at all, so vsync does not block the application while a window is hidden.
+    pub present_mode: Option<PresentMode>,
     /// Optionally sets the shader version for the window.
     pub shader: Option<egui_glow::ShaderVersion>,
+    /// The number of bits requested for the depth buffer. None means no depth buffer is requested.
+    /// When set, the depth buffer is cleared before every frame, just like the color buffer.
+    pub depth_bits: Option<u8>,
+    /// When no opengl config with the requested depth_bits works, use one with a smaller or no depth buffer instead of failing
+    /// to create the window. A warning is printed when this happens. Defaults to false.
+    pub allow_depth_fallback: bool,
+    /// The color used to clear the window before drawing each frame. The default is transparent.
+    pub clear_color: egui::Rgba,
+    /// Limits how often the window is redrawn, in frames per second. Repaints requested by egui sooner than this are delayed. None means no limit.
+    pub max_fps: Option<f32>,
+    /// When true, the window gets a [GlRenderTarget] the size of the window. It is bound while `opengl_before` runs,
+    /// and its contents are copied to the window before egui is painted on top. It has a depth buffer when depth_bits is set.
+    pub render_target: bool,
+    /// Should the window have decorations (title bar and borders). Defaults to true.
+    pub decorations: bool,
+    /// Should the window be transparent. This also requests an opengl config that supports transparency. Defaults to false.
+    pub transparent: bool,
+    /// The level of the window, such as always on top. Defaults to normal.
+    pub window_level: winit::window::WindowLevel,
+    /// When true, the window is created hidden and shown once its first frame has been painted and presented, avoiding a flash of an unpainted window.
+    pub visible_after_first_frame: bool,
+    /// The opengl contexts of windows in the same share group share textures, buffers, and other opengl objects,
+    /// so objects created in `opengl_before` of one window can be used by the others. egui itself still uploads its textures per window.
+    pub share_group: Option<GroupId>,
+    /// The opengl api used for the window. None uses the default of the platform: WGL on windows, EGL on linux, and CGL on macos.
+    /// GLX can help on linux systems where EGL does not work, and EGL on windows can be used through ANGLE.
+    pub gl_api_preference: Option<GlApiPreference>,
+    /// The kind of opengl context to create. None lets glutin decide, which is desktop opengl on most platforms.
+    /// sRGB framebuffers are only enabled for desktop opengl contexts.
+    pub context_api: Option<GlContextApi>,
+    /// Use an sRGB framebuffer, so colors are converted from linear when written. Only enabled when the opengl config of the window
+    /// supports it, and never for OpenGL ES. Defaults to true.
+    pub srgb: bool,
+    /// Should the window receive mouse input. When false, clicks pass through to whatever is beneath the window, which is useful
+    /// for overlays. egui widgets of the window do not receive any clicks while it is off. Not supported on every platform.
+    /// Can be changed at runtime with `MultiWindow::set_cursor_hittest`. Defaults to true.
+    pub cursor_hittest: bool,
+}
+
+impl TrackedWindowOptions {
+    /// The effective present mode, taking the deprecated vsync option into account
+    pub fn present_mode(&self) -> PresentMode {
+        #[allow(deprecated)]
+        let vsync = self.vsync;
+        match self.present_mode {
+            Some(mode) => mode,
+            None if vsync => PresentMode::Fifo,
+            None => PresentMode::Immediate,
+        }
+    }
+
+    /// Apply the window attributes of the options to a window builder. Attributes already changed from their defaults on the builder take precedence.
+    pub fn apply(&self, builder: winit::window::WindowBuilder) -> winit::window::WindowBuilder {
+        let attributes = builder.window_attributes();
+        let decorations = attributes.decorations && self.decorations;
+        let transparent = attributes.transparent || self.transparent;
+        let level = if attributes.window_level == winit::window::WindowLevel::Normal {
+            self.window_level
+        } else {
+            attributes.window_level
+        };
+        builder
+            .with_decorations(decorations)
+            .with_transparent(transparent)
+            .with_window_level(level)
+    }
+}
+
+impl Default for TrackedWindowOptions {
+    #[allow(deprecated)]
+    fn default() -> Self {
+        Self {
+            vsync: false,
+            present_mode: None,
+            shader: None,
+            depth_bits: None,
+            allow_depth_fallback: false,
+            clear_color: egui::Rgba::TRANSPARENT,
+            max_fps: None,
+            render_target: false,
+            decorations: true,
+            transparent: false,
+            window_level: winit::window::WindowLevel::Normal,
+            visible_after_first_frame: false,
+            share_group: None,
+            gl_api_preference: None,
+            context_api: None,
+            srgb: true,
+            cursor_hittest: true,
+        }
+    }
 }
 
 #[derive(Error, Debug)]
 /// Enumerates the kinds of errors that display creation can have.
 pub enum DisplayCreationError {}
+
+/// Allows retrieving a specific window type from the enum of all windows of an application.
+/// This is normally implemented with the [`window_variants`](macro.window_variants.html) macro.
+pub trait WindowVariant<W> {
+    /// Returns a reference to the contained window, only if the enum variant contains a window of type W.
+    fn as_variant(&self) -> Option<&W>;
+    /// Returns a mutable reference to the contained window, only if the enum variant contains a window of type W.
+    fn as_variant_mut(&mut self) -> Option<&mut W>;
+}
+
+/// Implements [WindowVariant] for each variant of the enum of all windows of an application. enum_dispatch only generates conversions by value,
+/// this macro provides conversions by reference.
+/// The first argument is the window enum, followed by each variant and the window type it contains.
+/// ```ignore
+/// egui_multiwin::window_variants!(MyWindows, Root(root::RootWindow), Popup(popup_window::PopupWindow));
+/// ```
+#[macro_export]
+macro_rules! window_variants {
+    ($enum:ty, $($variant:ident($window:ty)),* $(,)?) => {
+        $(
+            impl egui_multiwin::tracked_window::WindowVariant<$window> for $enum {
+                fn as_variant(&self) -> Option<&$window> {
+                    match self {
+                        Self::$variant(w) => Some(w),
+                        #[allow(unreachable_patterns)]
+                        _ => None,
+                    }
+                }
+
+                fn as_variant_mut(&mut self) -> Option<&mut $window> {
+                    match self {
+                        Self::$variant(w) => Some(w),
+                        #[allow(unreachable_patterns)]
+                        _ => None,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+/// Toggles borderless fullscreen on a window at runtime, remembering the windowed geometry so it can be restored afterwards.
+/// Keep one of these in the window struct and call [FullscreenToggle::toggle] from redraw.
+#[derive(Clone, Debug, Default)]
+pub struct FullscreenToggle {
+    /// The outer position, inner size, and maximized state of the window before it went fullscreen
+    saved: Option<(
+        Option<winit::dpi::PhysicalPosition<i32>>,
+        winit::dpi::PhysicalSize<u32>,
+        bool,
+    )>,
+}
+
+impl FullscreenToggle {
+    /// Returns true if the window is currently fullscreen, whether or not it went fullscreen through a toggle
+    pub fn is_fullscreen(window: &winit::window::Window) -> bool {
+        window.fullscreen().is_some()
+    }
+
+    /// Switch the window between windowed and borderless fullscreen. The window goes fullscreen on the monitor it is currently on.
+    pub fn toggle(&mut self, window: &winit::window::Window) {
+        if Self::is_fullscreen(window) {
+            window.set_fullscreen(None);
+            if let Some((position, size, maximized)) = self.saved.take() {
+                if maximized {
+                    window.set_maximized(true);
+                } else {
+                    // Move back first, so the size is applied on the monitor the window came from
+                    if let Some(position) = position {
+                        window.set_outer_position(position);
+                    }
+                    let _ = window.request_inner_size(size);
+                }
+            }
+        } else {
+            self.saved = Some((
+                window.outer_position().ok(),
+                window.inner_size(),
+                window.is_maximized(),
+            ));
+            window.set_fullscreen(Some(winit::window::Fullscreen::Borderless(
+                window.current_monitor(),
+            )));
+        }
+    }
+}
+
+thread_local! {
+    /// The windows that currently have the cursor grabbed by [grab_cursor]
+    static CURSOR_GRABS: std::cell::RefCell<std::collections::HashSet<winit::window::WindowId>> =
+        std::cell::RefCell::new(std::collections::HashSet::new());
+}
+
+/// Capture the mouse for a window, hiding the cursor and keeping it inside the window, such as for the look controls of a 3d view.
+/// Locking the cursor in place is tried first, with confining the cursor to the window as the fallback, because platforms support
+/// different modes. Returns the mode used. The grab is released automatically when the window loses focus.
+/// Cursor movement while locked is only reported as device events, see `CommonEventHandler::device_event`.
+pub fn grab_cursor(
+    window: &winit::window::Window,
+) -> Result<winit::window::CursorGrabMode, winit::error::ExternalError> {
+    let mode = match window.set_cursor_grab(winit::window::CursorGrabMode::Locked) {
+        Ok(()) => winit::window::CursorGrabMode::Locked,
+        Err(_) => {
+            window.set_cursor_grab(winit::window::CursorGrabMode::Confined)?;
+            winit::window::CursorGrabMode::Confined
+        }
+    };
+    window.set_cursor_visible(false);
+    CURSOR_GRABS.with(|g| g.borrow_mut().insert(window.id()));
+    Ok(mode)
+}
+
+/// Release the mouse captured with [grab_cursor], showing the cursor again. Does nothing if the cursor is not grabbed.
+pub fn release_cursor(window: &winit::window::Window) {
+    if CURSOR_GRABS.with(|g| g.borrow_mut().remove(&window.id())) {
+        let _e = window.set_cursor_grab(winit::window::CursorGrabMode::None);
+        window.set_cursor_visible(true);
+    }
+}
+
+/// Returns true if the cursor is grabbed by the window with [grab_cursor]
+pub fn is_cursor_grabbed(window: &winit::window::Window) -> bool {
+    CURSOR_GRABS.with(|g| g.borrow().contains(&window.id()))
+}
+
+/// An offscreen framebuffer with a color texture and an optional depth buffer, for rendering that is composited into a window.
+/// The opengl objects are not freed on drop, [GlRenderTarget::destroy] must be called with the context that created them.
+pub struct GlRenderTarget {
+    /// The framebuffer object
+    framebuffer: glow::Framebuffer,
+    /// The color attachment
+    color: glow::Texture,
+    /// The optional depth attachment
+    depth: Option<glow::Renderbuffer>,
+    /// The size of the attachments, in pixels
+    size: (u32, u32),
+}
+
+impl GlRenderTarget {
+    /// Create a render target of the given size, in pixels, with an optional depth buffer.
+    /// # Safety
+    ///
+    /// The opengl context of gl must be current.
+    pub unsafe fn new(gl: &glow::Context, size: (u32, u32), depth: bool) -> Result<Self, String> {
+        use glow::HasContext as _;
+        let framebuffer = gl.create_framebuffer()?;
+        let color = gl.create_texture()?;
+        let depth = if depth {
+            Some(gl.create_renderbuffer()?)
+        } else {
+            None
+        };
+        let mut s = Self {
+            framebuffer,
+            color,
+            depth,
+            size: (0, 0),
+        };
+        s.resize(gl, size);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(framebuffer));
+        gl.framebuffer_texture_2d(
+            glow::FRAMEBUFFER,
+            glow::COLOR_ATTACHMENT0,
+            glow::TEXTURE_2D,
+            Some(color),
+            0,
+        );
+        if let Some(depth) = depth {
+            gl.framebuffer_renderbuffer(
+                glow::FRAMEBUFFER,
+                glow::DEPTH_ATTACHMENT,
+                glow::RENDERBUFFER,
+                Some(depth),
+            );
+        }
+        let status = gl.check_framebuffer_status(glow::FRAMEBUFFER);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+        if status != glow::FRAMEBUFFER_COMPLETE {
+            s.destroy(gl);
+            return Err(format!("Incomplete framebuffer, status {:x}", status));
+        }
+        Ok(s)
+    }
+
+    /// Resize the attachments, does nothing if the size is unchanged. The contents are undefined after a resize.
+    /// # Safety
+    ///
+    /// The opengl context of gl must be current.
+    pub unsafe fn resize(&mut self, gl: &glow::Context, size: (u32, u32)) {
+        use glow::HasContext as _;
+        let size = (size.0.max(1), size.1.max(1));
+        if size == self.size {
+            return;
+        }
+        self.size = size;
+        gl.bind_texture(glow::TEXTURE_2D, Some(self.color));
+        gl.tex_image_2d(
+            glow::TEXTURE_2D,
+            0,
+            glow::RGBA8 as i32,
+            size.0 as i32,
+            size.1 as i32,
+            0,
+            glow::RGBA,
+            glow::UNSIGNED_BYTE,
+            None,
+        );
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MIN_FILTER, glow::LINEAR as i32);
+        gl.tex_parameter_i32(glow::TEXTURE_2D, glow::TEXTURE_MAG_FILTER, glow::LINEAR as i32);
+        gl.bind_texture(glow::TEXTURE_2D, None);
+        if let Some(depth) = self.depth {
+            gl.bind_renderbuffer(glow::RENDERBUFFER, Some(depth));
+            gl.renderbuffer_storage(
+                glow::RENDERBUFFER,
+                glow::DEPTH_COMPONENT24,
+                size.0 as i32,
+                size.1 as i32,
+            );
+            gl.bind_renderbuffer(glow::RENDERBUFFER, None);
+        }
+    }
+
+    /// The size of the render target, in pixels
+    pub fn size(&self) -> (u32, u32) {
+        self.size
+    }
+
+    /// The color texture, for sampling the rendered image
+    pub fn texture(&self) -> glow::Texture {
+        self.color
+    }
+
+    /// Bind the render target for drawing, and set the viewport to cover all of it
+    /// # Safety
+    ///
+    /// The opengl context of gl must be current.
+    pub unsafe fn bind(&self, gl: &glow::Context) {
+        use glow::HasContext as _;
+        gl.bind_framebuffer(glow::FRAMEBUFFER, Some(self.framebuffer));
+        gl.viewport(0, 0, self.size.0 as i32, self.size.1 as i32);
+    }
+
+    /// Bind the default framebuffer of the window again
+    /// # Safety
+    ///
+    /// The opengl context of gl must be current.
+    pub unsafe fn unbind(&self, gl: &glow::Context) {
+        use glow::HasContext as _;
+        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+    }
+
+    /// Copy the color contents to the default framebuffer of the window, leaving the default framebuffer bound
+    /// # Safety
+    ///
+    /// The opengl context of gl must be current.
+    pub unsafe fn blit_to_window(&self, gl: &glow::Context) {
+        use glow::HasContext as _;
+        let (w, h) = (self.size.0 as i32, self.size.1 as i32);
+        gl.bind_framebuffer(glow::READ_FRAMEBUFFER, Some(self.framebuffer));
+        gl.bind_framebuffer(glow::DRAW_FRAMEBUFFER, None);
+        gl.blit_framebuffer(0, 0, w, h, 0, 0, w, h, glow::COLOR_BUFFER_BIT, glow::NEAREST);
+        gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+    }
+
+    /// Free the opengl objects of the render target
+    /// # Safety
+    ///
+    /// The opengl context that created the render target must be current.
+    pub unsafe fn destroy(self, gl: &glow::Context) {
+        use glow::HasContext as _;
+        gl.delete_framebuffer(self.framebuffer);
+        gl.delete_texture(self.color);
+        if let Some(depth) = self.depth {
+            gl.delete_renderbuffer(depth);
+        }
+    }
+}
+
+/// Returns true when the `software-fallback` feature is enabled. Used by the code generated by the [`tracked_window`](macro.tracked_window.html) macro.
+#[doc(hidden)]
+pub fn software_fallback() -> bool {
+    cfg!(feature = "software-fallback")
+}
+
+/// Read the back buffer of the default framebuffer of a window, before it is swapped, into an image of the specified size.
+/// # Safety
+///
+/// The opengl context of the window must be current.
+pub unsafe fn read_back_buffer(gl: &glow::Context, size: (u32, u32)) -> egui::ColorImage {
+    use glow::HasContext as _;
+    let (w, h) = (size.0 as usize, size.1 as usize);
+    let mut pixels = vec![0u8; w * h * 4];
+    gl.bind_framebuffer(glow::FRAMEBUFFER, None);
+    gl.read_buffer(glow::BACK);
+    gl.pixel_store_i32(glow::PACK_ALIGNMENT, 1);
+    gl.read_pixels(
+        0,
+        0,
+        w as i32,
+        h as i32,
+        glow::RGBA,
+        glow::UNSIGNED_BYTE,
+        glow::PixelPackData::Slice(&mut pixels),
+    );
+    // opengl rows start at the bottom of the image
+    let flipped: Vec<u8> = pixels.chunks_exact(w * 4).rev().flatten().copied().collect();
+    egui::ColorImage::from_rgba_unmultiplied([w, h], &flipped)
+}
+
+/// Returns true for the events that come from user input, such as the keyboard, the mouse, and touch screens.
+/// These are the events held back from egui while a modal dialog of the window is open.
+pub fn is_input_event(event: &winit::event::WindowEvent) -> bool {
+    use winit::event::WindowEvent;
+    matches!(
+        event,
+        WindowEvent::KeyboardInput { .. }
+            | WindowEvent::Ime(_)
+            | WindowEvent::CursorMoved { .. }
+            | WindowEvent::MouseWheel { .. }
+            | WindowEvent::MouseInput { .. }
+            | WindowEvent::TouchpadMagnify { .. }
+            | WindowEvent::SmartMagnify { .. }
+            | WindowEvent::TouchpadRotate { .. }
+            | WindowEvent::TouchpadPressure { .. }
+            | WindowEvent::AxisMotion { .. }
+            | WindowEvent::Touch(_)
+    )
+}
+
+/// Install the image loaders of egui_extras on an egui context, so that images can be loaded by uri, like `ui.image("file://image.png")`.
+/// Requires the `image_loaders` feature, without it this does nothing. Every window has its own egui context,
+/// see `MultiWindow::install_image_loaders` to install them on every window.
+pub fn install_image_loaders(ctx: &egui::Context) {
+    #[cfg(feature = "image_loaders")]
+    egui_extras::install_image_loaders(ctx);
+    #[cfg(not(feature = "image_loaders"))]
+    let _ = ctx;
+}
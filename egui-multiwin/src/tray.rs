@@ -0,0 +1,60 @@
+//! This module contains the optional system tray icon integration, based on the tray-icon crate. Only available with the `tray` feature.
+//!
+//! Create the tray icon with [create], then forward clicks on the icon with [forward_events] and activations of its menu with
+//! [crate::menu::forward_events]. Both are delivered as custom events, so `CommonEventHandler::process_event` can create windows
+//! in response, like any other custom event. The application keeps running while its windows are hidden, so hiding windows instead of
+//! closing them keeps the application in the tray.
+//!
+//! On linux, tray-icon requires gtk to be initialized on the thread that creates the icon.
+//! ```ignore
+//! let menu = egui_multiwin::muda::Menu::new();
+//! menu.append(&egui_multiwin::muda::MenuItem::with_id("show", "Show", true, None)).unwrap();
+//! // Keep the tray icon alive for as long as it should be shown, in the common data for example
+//! let tray = egui_multiwin::tray::create(menu, rgba, 32, 32, "My application").unwrap();
+//! egui_multiwin::menu::forward_events(proxy.clone(), |e| CustomEvent::Menu(e.id));
+//! egui_multiwin::tray::forward_events(proxy, CustomEvent::Tray);
+//! ```
+
+/// Errors that can occur when creating a tray icon
+#[derive(thiserror::Error, Debug)]
+pub enum TrayError {
+    /// The icon data is not valid
+    #[error("The icon is not valid: {0}")]
+    Icon(#[from] tray_icon::BadIcon),
+    /// The tray icon could not be created
+    #[error("The tray icon could not be created: {0}")]
+    Creation(#[from] tray_icon::Error),
+}
+
+/// Create a tray icon with a menu, from rgba icon data. The data must be 4 bytes per pixel, with rows tightly packed, for a total of
+/// width * height * 4 bytes. The icon is removed from the tray when the returned value is dropped.
+pub fn create(
+    menu: muda::Menu,
+    rgba: Vec<u8>,
+    width: u32,
+    height: u32,
+    tooltip: &str,
+) -> Result<tray_icon::TrayIcon, TrayError> {
+    let icon = tray_icon::Icon::from_rgba(rgba, width, height)?;
+    let tray = tray_icon::TrayIconBuilder::new()
+        .with_menu(Box::new(menu))
+        .with_icon(icon)
+        .with_tooltip(tooltip)
+        .build()?;
+    Ok(tray)
+}
+
+/// Deliver every event of the tray icon itself (clicks on the icon) to the event loop as a custom event, created from the tray event by
+/// the given function. Activations of the menu of the tray icon are menu events, see [crate::menu::forward_events].
+/// This replaces any previous tray event handler.
+pub fn forward_events<T: Send + 'static>(
+    proxy: winit::event_loop::EventLoopProxy<T>,
+    f: impl Fn(tray_icon::TrayIconEvent) -> T + Send + Sync + 'static,
+) {
+    let proxy = std::sync::Mutex::new(proxy);
+    tray_icon::TrayIconEvent::set_event_handler(Some(move |e| {
+        if let Ok(proxy) = proxy.lock() {
+            let _e = proxy.send_event(f(e));
+        }
+    }));
+}
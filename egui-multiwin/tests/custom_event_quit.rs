@@ -0,0 +1,113 @@
+//! A window closes itself when its custom_event function returns quit. This needs a display, run it with `cargo test -- --ignored`.
+
+egui_multiwin::window_enum! {
+    common: crate::AppCommon,
+    event: crate::CustomEvent,
+    /// The windows of the test
+    pub enum MyWindows {
+        /// The window that closes on the shutdown event
+        Shutdown(ShutdownWindow),
+    }
+}
+
+use egui_multiwin::multi_window::WindowTarget;
+use egui_multiwin_dynamic::multi_window::{CommonEventHandler, MultiWindow, NewWindowRequest};
+use egui_multiwin_dynamic::tracked_window::{RedrawContext, RedrawResponse, TrackedWindow};
+
+/// The common data of the test
+pub struct AppCommon {}
+
+impl CommonEventHandler for AppCommon {
+    fn process_event(&mut self, _event: CustomEvent) -> Vec<NewWindowRequest> {
+        Vec::new()
+    }
+}
+
+/// The events of the test
+#[derive(Debug)]
+pub enum CustomEvent {
+    /// Asks the target window to close
+    Shutdown(WindowTarget),
+}
+
+impl CustomEvent {
+    /// The window the event is for
+    fn window_id(&self) -> Option<egui_multiwin::winit::window::WindowId> {
+        match self {
+            CustomEvent::Shutdown(target) => target.window_id(),
+        }
+    }
+}
+
+/// A window that closes when it receives the shutdown event
+pub struct ShutdownWindow {}
+
+impl TrackedWindow for ShutdownWindow {
+    fn is_root(&self) -> bool {
+        true
+    }
+
+    fn custom_event(&mut self, event: &CustomEvent, _ctx: &mut RedrawContext) -> RedrawResponse {
+        RedrawResponse {
+            quit: matches!(event, CustomEvent::Shutdown(_)),
+            new_windows: Vec::new(),
+            repaint: false,
+        }
+    }
+
+    fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+        egui_multiwin::egui::CentralPanel::default().show(&ctx.egui.egui_ctx, |ui| {
+            ui.label("Waiting for the shutdown event");
+        });
+        RedrawResponse::default()
+    }
+}
+
+#[test]
+#[ignore = "needs a display"]
+fn shutdown_event_closes_window() {
+    let mut builder = egui_multiwin::winit::event_loop::EventLoopBuilder::with_user_event();
+    // Tests do not run on the main thread
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        use egui_multiwin::winit::platform::x11::EventLoopBuilderExtX11;
+        builder.with_any_thread(true);
+    }
+    #[cfg(target_os = "windows")]
+    {
+        use egui_multiwin::winit::platform::windows::EventLoopBuilderExtWindows;
+        builder.with_any_thread(true);
+    }
+    let mut event_loop = builder.build().unwrap();
+    let mut multi_window = MultiWindow::new();
+    let mut common = AppCommon {};
+
+    let request = NewWindowRequest::builder(MyWindows::Shutdown(ShutdownWindow {}))
+        .title("shutdown test")
+        .build()
+        .unwrap()
+        .with_role("shutdown");
+    let id = request.id;
+    multi_window.add(request, &mut common, &event_loop).unwrap();
+    assert_eq!(multi_window.window_by_role("shutdown"), Some(id));
+
+    // Let the window initialize before asking it to close
+    let timeout = Some(std::time::Duration::from_millis(10));
+    for _ in 0..10 {
+        multi_window.run_return(&mut event_loop, &mut common, timeout);
+    }
+    event_loop
+        .create_proxy()
+        .send_event(CustomEvent::Shutdown(WindowTarget::Request(id)))
+        .unwrap();
+
+    let mut exited = false;
+    for _ in 0..100 {
+        exited = multi_window.run_return(&mut event_loop, &mut common, timeout).1;
+        if exited {
+            break;
+        }
+    }
+    assert!(exited, "the application should exit once its only window closes");
+    assert_eq!(multi_window.window_by_role("shutdown"), None);
+}
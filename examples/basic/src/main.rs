@@ -33,19 +33,31 @@ use windows::{
 pub struct AppCommon {
     /// Number of times a button has been clicked
     clicks: u32,
+    /// True when the groot viewport should be visible
+    summon_groot: bool,
+    /// Used by the confirmation dialog to quit the application
+    quit: egui_multiwin::multi_window::QuitHandle,
 }
 
-impl AppCommon {
+impl crate::egui_multiwin_dynamic::multi_window::CommonEventHandler for AppCommon {
     /// Process events
     fn process_event(&mut self, _event: egui_multiwin::NoEvent) -> Vec<NewWindowRequest> {
         Vec::new()
     }
+
+    fn viewport_closed(&mut self, id: egui_multiwin::egui::viewport::ViewportId) {
+        if id == windows::root::groot_viewport_id() {
+            self.summon_groot = false;
+        }
+    }
 }
 
 fn main() {
     let mut event_loop = egui_multiwin::winit::event_loop::EventLoopBuilder::with_user_event();
     let event_loop = event_loop.build().unwrap();
     let mut multi_window: MultiWindow = MultiWindow::new();
+    multi_window.set_reduced_motion(MultiWindow::prefers_reduced_motion());
+    multi_window.set_visuals(egui_multiwin::egui::Visuals::dark());
     multi_window.add_font(
         "computermodern".to_string(),
         egui_multiwin::egui::FontData::from_static(COMPUTER_MODERN_FONT),
@@ -53,7 +65,11 @@ fn main() {
     let root_window = root::RootWindow::request();
     let root_window2 = popup_window::PopupWindow::request("initial popup".to_string());
 
-    let mut ac = AppCommon { clicks: 0 };
+    let mut ac = AppCommon {
+        clicks: 0,
+        summon_groot: false,
+        quit: multi_window.quit_handle(),
+    };
 
     let _e = multi_window.add(root_window, &mut ac, &event_loop);
     let _e = multi_window.add(root_window2, &mut ac, &event_loop);
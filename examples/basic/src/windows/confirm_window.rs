@@ -0,0 +1,48 @@
+//! A modal dialog that asks for confirmation before quitting the application
+use crate::egui_multiwin_dynamic::{
+    multi_window::NewWindowRequest,
+    tracked_window::{RedrawContext, RedrawResponse, TrackedWindow},
+};
+
+/// The confirmation dialog
+pub struct ConfirmWindow {}
+
+impl ConfirmWindow {
+    /// Request a new dialog, modal to the window with the given window request id
+    pub fn request(parent: u32) -> NewWindowRequest {
+        NewWindowRequest::builder(super::MyWindows::Confirm(ConfirmWindow {}))
+            .title("Quit?")
+            .inner_size(300.0, 100.0)
+            .resizable(false)
+            .build()
+            .unwrap()
+            .modal_for(parent)
+    }
+}
+
+impl TrackedWindow for ConfirmWindow {
+    fn share_style_group(&self) -> Option<egui_multiwin::tracked_window::GroupId> {
+        Some(super::STYLE_GROUP)
+    }
+
+    fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+        let RedrawContext { common: c, egui, .. } = ctx;
+        let mut quit = false;
+        egui_multiwin::egui::CentralPanel::default().show(&egui.egui_ctx, |ui| {
+            ui.label("Are you sure you want to quit?");
+            ui.horizontal(|ui| {
+                if ui.button("Yes").clicked() {
+                    c.quit.quit();
+                }
+                if ui.button("No").clicked() {
+                    quit = true;
+                }
+            });
+        });
+        RedrawResponse {
+            quit,
+            new_windows: Vec::new(),
+            repaint: false,
+        }
+    }
+}
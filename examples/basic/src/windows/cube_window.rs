@@ -0,0 +1,218 @@
+//! This is an example of a window that draws a spinning cube with depth testing underneath the egui interface.
+use crate::egui_multiwin_dynamic::{
+    multi_window::NewWindowRequest,
+    tracked_window::{RedrawContext, RedrawResponse, TrackedWindow},
+};
+use egui_multiwin::egui;
+use egui_multiwin::egui_glow::glow;
+
+use crate::AppCommon;
+
+/// The cube window
+pub struct CubeWindow {
+    /// The rotation of the cube, advanced every frame
+    angle: f32,
+    /// The shader program for the cube, created on first use
+    program: Option<glow::Program>,
+    /// The vertex array used for drawing the cube, created on first use
+    vertex_array: Option<glow::VertexArray>,
+}
+
+impl CubeWindow {
+    /// Request the cube window. There is only one, requesting it again focuses the existing window.
+    pub fn request() -> NewWindowRequest {
+        NewWindowRequest::new(
+            super::MyWindows::Cube(CubeWindow {
+                angle: 0.0,
+                program: None,
+                vertex_array: None,
+            }),
+            egui_multiwin::winit::window::WindowBuilder::new()
+                .with_resizable(true)
+                .with_inner_size(egui_multiwin::winit::dpi::LogicalSize {
+                    width: 400.0,
+                    height: 400.0,
+                })
+                .with_title("Depth tested cube"),
+            egui_multiwin::tracked_window::TrackedWindowOptions {
+                shader: None,
+                depth_bits: Some(24),
+                ..Default::default()
+            },
+            egui_multiwin::multi_window::new_id().unwrap(),
+        )
+        .with_role("cube")
+    }
+
+    /// Compile the shader program used to draw the cube
+    unsafe fn create_program(
+        gl: &std::sync::Arc<egui_multiwin::egui_glow::painter::Context>,
+    ) -> glow::Program {
+        use glow::HasContext;
+        let shader_version = egui_multiwin::egui_glow::ShaderVersion::get(gl);
+        let program = gl.create_program().expect("Cannot create program");
+        let (vertex_shader_source, fragment_shader_source) = (
+            r#"const vec3 corners[8] = vec3[8](
+                vec3(-0.5, -0.5, -0.5),
+                vec3(0.5, -0.5, -0.5),
+                vec3(0.5, 0.5, -0.5),
+                vec3(-0.5, 0.5, -0.5),
+                vec3(-0.5, -0.5, 0.5),
+                vec3(0.5, -0.5, 0.5),
+                vec3(0.5, 0.5, 0.5),
+                vec3(-0.5, 0.5, 0.5)
+            );
+            const int indices[36] = int[36](
+                0, 2, 1, 0, 3, 2,
+                4, 5, 6, 4, 6, 7,
+                0, 1, 5, 0, 5, 4,
+                3, 6, 2, 3, 7, 6,
+                0, 4, 7, 0, 7, 3,
+                1, 2, 6, 1, 6, 5
+            );
+            const vec3 colors[6] = vec3[6](
+                vec3(1.0, 0.0, 0.0),
+                vec3(0.0, 1.0, 0.0),
+                vec3(0.0, 0.0, 1.0),
+                vec3(1.0, 1.0, 0.0),
+                vec3(0.0, 1.0, 1.0),
+                vec3(1.0, 0.0, 1.0)
+            );
+            uniform float angle;
+            out vec3 v_color;
+            void main() {
+                vec3 p = corners[indices[gl_VertexID]];
+                float c = cos(angle);
+                float s = sin(angle);
+                p = vec3(c * p.x + s * p.z, p.y, c * p.z - s * p.x);
+                float c2 = cos(angle * 0.7);
+                float s2 = sin(angle * 0.7);
+                p = vec3(p.x, c2 * p.y - s2 * p.z, s2 * p.y + c2 * p.z);
+                p.z -= 2.0;
+                gl_Position = vec4(p.x * 1.5, p.y * 1.5, p.z * -1.0202 - 0.202, -p.z);
+                v_color = colors[gl_VertexID / 6];
+            }"#,
+            r#"precision mediump float;
+            in vec3 v_color;
+            out vec4 color;
+            void main() {
+                color = vec4(v_color, 1.0);
+            }"#,
+        );
+
+        let shader_sources = [
+            (glow::VERTEX_SHADER, vertex_shader_source),
+            (glow::FRAGMENT_SHADER, fragment_shader_source),
+        ];
+        let mut shaders = Vec::with_capacity(shader_sources.len());
+        for (shader_type, shader_source) in shader_sources.iter() {
+            let shader = gl
+                .create_shader(*shader_type)
+                .expect("Cannot create shader");
+            gl.shader_source(
+                shader,
+                &format!(
+                    "{}\n{}",
+                    shader_version.version_declaration(),
+                    shader_source
+                ),
+            );
+            gl.compile_shader(shader);
+            if !gl.get_shader_compile_status(shader) {
+                panic!("{}", gl.get_shader_info_log(shader));
+            }
+            gl.attach_shader(program, shader);
+            shaders.push(shader);
+        }
+        gl.link_program(program);
+        if !gl.get_program_link_status(program) {
+            panic!("{}", gl.get_program_info_log(program));
+        }
+
+        for shader in shaders {
+            gl.detach_shader(program, shader);
+            gl.delete_shader(shader);
+        }
+        program
+    }
+}
+
+impl TrackedWindow for CubeWindow {
+    fn share_style_group(&self) -> Option<egui_multiwin::tracked_window::GroupId> {
+        Some(super::STYLE_GROUP)
+    }
+
+    fn continuous(&self) -> bool {
+        true
+    }
+
+    fn window_created(&mut self, window: &egui_multiwin::winit::window::Window) {
+        // The cube needs some room, so enforce a minimum size once the window exists
+        window.set_min_inner_size(Some(egui_multiwin::winit::dpi::LogicalSize::new(
+            200.0, 200.0,
+        )));
+    }
+
+    unsafe fn opengl_before(
+        &mut self,
+        _c: &mut AppCommon,
+        gl: &std::sync::Arc<egui_multiwin::egui_glow::painter::Context>,
+        window: &egui_multiwin::winit::window::Window,
+    ) {
+        use glow::HasContext;
+        let size = window.inner_size();
+        gl.viewport(0, 0, size.width as i32, size.height as i32);
+        let program = *self
+            .program
+            .get_or_insert_with(|| Self::create_program(gl));
+        let vertex_array = *self.vertex_array.get_or_insert_with(|| {
+            gl.create_vertex_array()
+                .expect("Cannot create vertex array")
+        });
+
+        gl.enable(glow::DEPTH_TEST);
+        gl.depth_func(glow::LESS);
+        gl.use_program(Some(program));
+        gl.bind_vertex_array(Some(vertex_array));
+        let angle = gl.get_uniform_location(program, "angle");
+        gl.uniform_1_f32(angle.as_ref(), self.angle);
+        gl.draw_arrays(glow::TRIANGLES, 0, 36);
+        gl.bind_vertex_array(None);
+        gl.use_program(None);
+        gl.disable(glow::DEPTH_TEST);
+    }
+
+    fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+        let RedrawContext { egui, window, dt, .. } = ctx;
+        let mut quit = false;
+
+        self.angle += dt.as_secs_f32();
+
+        let style = egui::style::Style::default();
+        let mut frame = egui::containers::Frame::central_panel(&style);
+        frame.fill = egui::Color32::from_white_alpha(0);
+        egui_multiwin::egui::CentralPanel::default()
+            .frame(frame)
+            .show(&egui.egui_ctx, |ui| {
+                ui.label("This cube is drawn with depth testing enabled");
+                if egui_multiwin::tracked_window::is_cursor_grabbed(window) {
+                    ui.label("The mouse is captured, press escape to release it");
+                    if ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                        egui_multiwin::tracked_window::release_cursor(window);
+                    }
+                } else if ui.button("Capture mouse").clicked() {
+                    if let Err(e) = egui_multiwin::tracked_window::grab_cursor(window) {
+                        println!("Failed to capture the mouse {:?}", e);
+                    }
+                }
+                if ui.button("Quit").clicked() {
+                    quit = true;
+                }
+            });
+        RedrawResponse {
+            quit,
+            new_windows: Vec::new(),
+            repaint: false,
+        }
+    }
+}
@@ -2,14 +2,19 @@
 
 use egui_multiwin::enum_dispatch::enum_dispatch;
 
-use crate::egui_multiwin_dynamic::tracked_window::{RedrawResponse, TrackedWindow};
-use egui_multiwin::egui_glow::EguiGlow;
+use crate::egui_multiwin_dynamic::tracked_window::{RedrawContext, RedrawResponse, TrackedWindow};
 use std::sync::Arc;
 
+pub mod confirm_window;
+pub mod cube_window;
 pub mod popup_window;
 pub mod root;
 pub mod transparent_window;
 
+/// All windows of the program share this style group, so the dark/light toggle of the root window applies everywhere
+pub const STYLE_GROUP: egui_multiwin::tracked_window::GroupId =
+    egui_multiwin::tracked_window::GroupId(0);
+
 /// The windows for the program
 #[enum_dispatch(TrackedWindow)]
 pub enum MyWindows {
@@ -19,4 +24,17 @@ pub enum MyWindows {
     Popup(popup_window::PopupWindow),
     /// A popup window with a transparent background
     Transparent(transparent_window::PopupWindow),
+    /// A window that draws a depth tested cube
+    Cube(cube_window::CubeWindow),
+    /// A modal dialog confirming that the application should quit
+    Confirm(confirm_window::ConfirmWindow),
 }
+
+egui_multiwin::window_variants!(
+    MyWindows,
+    Root(root::RootWindow),
+    Popup(popup_window::PopupWindow),
+    Transparent(transparent_window::PopupWindow),
+    Cube(cube_window::CubeWindow),
+    Confirm(confirm_window::ConfirmWindow),
+);
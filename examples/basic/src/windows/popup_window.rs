@@ -1,10 +1,9 @@
 //! This is an example of a popup window. It is likely very crude on the opengl_after function and could probably be optimized
 use crate::egui_multiwin_dynamic::{
     multi_window::NewWindowRequest,
-    tracked_window::{RedrawResponse, TrackedWindow},
+    tracked_window::{RedrawContext, RedrawResponse, TrackedWindow},
 };
 use egui_multiwin::egui_glow::glow;
-use egui_multiwin::egui_glow::EguiGlow;
 
 use crate::AppCommon;
 
@@ -17,31 +16,28 @@ pub struct PopupWindow {
 impl PopupWindow {
     /// Request a new window
     pub fn request(label: String) -> NewWindowRequest {
-        NewWindowRequest::new(
-            super::MyWindows::Popup(PopupWindow {
-                input: label.clone(),
-            }),
-            egui_multiwin::winit::window::WindowBuilder::new()
-                .with_resizable(false)
-                .with_inner_size(egui_multiwin::winit::dpi::LogicalSize {
-                    width: 400.0,
-                    height: 200.0,
-                })
-                .with_title(label),
-            egui_multiwin::tracked_window::TrackedWindowOptions {
-                vsync: false,
-                shader: None,
-            },
-            egui_multiwin::multi_window::new_id(),
-        )
+        NewWindowRequest::builder(super::MyWindows::Popup(PopupWindow {
+            input: label.clone(),
+        }))
+        .title(label)
+        .inner_size(400.0, 200.0)
+        .resizable(false)
+        .build()
+        .unwrap()
+        .with_initial_focus(egui_multiwin::egui::Id::new("popup_input"))
     }
 }
 
 impl TrackedWindow for PopupWindow {
+    fn share_style_group(&self) -> Option<egui_multiwin::tracked_window::GroupId> {
+        Some(super::STYLE_GROUP)
+    }
+
     unsafe fn opengl_after(
         &mut self,
         _c: &mut AppCommon,
         gl: &std::sync::Arc<egui_multiwin::egui_glow::painter::Context>,
+        _window: &egui_multiwin::winit::window::Window,
     ) {
         use glow::HasContext;
         let shader_version = egui_multiwin::egui_glow::ShaderVersion::get(gl);
@@ -112,13 +108,8 @@ impl TrackedWindow for PopupWindow {
         (c.clicks & 1) == 0
     }
 
-    fn redraw(
-        &mut self,
-        c: &mut AppCommon,
-        egui: &mut EguiGlow,
-        window: &egui_multiwin::winit::window::Window,
-        _clipboard: &mut egui_multiwin::arboard::Clipboard,
-    ) -> RedrawResponse {
+    fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+        let RedrawContext { common: c, egui, window, .. } = ctx;
         let mut quit = false;
 
         egui_multiwin::egui::CentralPanel::default().show(&egui.egui_ctx, |ui| {
@@ -126,7 +117,10 @@ impl TrackedWindow for PopupWindow {
                 c.clicks += 1;
                 window.set_title(&format!("Title update {}", c.clicks));
             }
-            let response = ui.add(egui_multiwin::egui::TextEdit::singleline(&mut self.input));
+            let response = ui.add(
+                egui_multiwin::egui::TextEdit::singleline(&mut self.input)
+                    .id(egui_multiwin::egui::Id::new("popup_input")),
+            );
             if response.changed() {
                 // …
             }
@@ -141,6 +135,7 @@ impl TrackedWindow for PopupWindow {
         RedrawResponse {
             quit,
             new_windows: Vec::new(),
+            repaint: false,
         }
     }
 }
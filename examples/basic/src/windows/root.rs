@@ -4,12 +4,9 @@ use std::time::Duration;
 
 use crate::egui_multiwin_dynamic::{
     multi_window::NewWindowRequest,
-    tracked_window::{RedrawResponse, TrackedWindow},
+    tracked_window::{RedrawContext, RedrawResponse, TrackedWindow},
 };
 use egui_multiwin::egui::FontId;
-use egui_multiwin::egui_glow::EguiGlow;
-
-use crate::AppCommon;
 
 use super::popup_window::PopupWindow;
 
@@ -19,24 +16,30 @@ pub struct RootWindow {
     pub button_press_count: u32,
     /// The number of popus created
     pub num_popups_created: u32,
-    /// True when the groot viewport should be visible
-    summon_groot: bool,
-    /// The last time an update was performed
-    prev_time: std::time::Instant,
     /// The calculated frames per second of the application
     fps: Option<f32>,
+    /// Used to switch the window to and from fullscreen
+    fullscreen: egui_multiwin::tracked_window::FullscreenToggle,
+    /// The window request id of the root window, used to make dialogs modal to it
+    id: u32,
+}
+
+/// The id of the groot viewport
+pub fn groot_viewport_id() -> egui_multiwin::egui::viewport::ViewportId {
+    egui_multiwin::egui::viewport::ViewportId::from_hash_of("Testing")
 }
 
 impl RootWindow {
     /// Request a new window
     pub fn request() -> NewWindowRequest {
+        let id = egui_multiwin::multi_window::new_id().unwrap();
         NewWindowRequest::new(
             super::MyWindows::Root(RootWindow {
                 button_press_count: 0,
                 num_popups_created: 0,
-                summon_groot: false,
-                prev_time: std::time::Instant::now(),
                 fps: None,
+                fullscreen: Default::default(),
+                id,
             }),
             egui_multiwin::winit::window::WindowBuilder::new()
                 .with_resizable(true)
@@ -46,37 +49,36 @@ impl RootWindow {
                 })
                 .with_title("egui-multiwin root window"),
             egui_multiwin::tracked_window::TrackedWindowOptions {
-                vsync: false,
                 shader: None,
+                ..Default::default()
             },
-            egui_multiwin::multi_window::new_id(),
+            id,
         )
     }
 }
 
 impl TrackedWindow for RootWindow {
+    fn share_style_group(&self) -> Option<egui_multiwin::tracked_window::GroupId> {
+        Some(super::STYLE_GROUP)
+    }
+
     fn is_root(&self) -> bool {
         true
     }
 
     fn set_root(&mut self, _root: bool) {}
 
-    fn redraw(
-        &mut self,
-        c: &mut AppCommon,
-        egui: &mut EguiGlow,
-        _window: &egui_multiwin::winit::window::Window,
-        _clipboard: &mut egui_multiwin::arboard::Clipboard,
-    ) -> RedrawResponse {
-        let mut quit = false;
+    fn allow_zoom(&self) -> bool {
+        true
+    }
+
+    fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+        let RedrawContext { common: c, egui, window, dt, .. } = ctx;
+        let quit = false;
 
         egui.egui_ctx.request_repaint_after(Duration::from_millis(95));
 
-        let cur_time = std::time::Instant::now();
-        let delta = cur_time.duration_since(self.prev_time);
-        self.prev_time = cur_time;
-
-        let new_fps = 1_000_000_000.0 / delta.as_nanos() as f32;
+        let new_fps = 1_000_000_000.0 / dt.as_nanos() as f32;
         if let Some(fps) = &mut self.fps {
             *fps = (*fps * 0.95) + (0.05 * new_fps);
         } else {
@@ -99,8 +101,22 @@ impl TrackedWindow for RootWindow {
                     "Transparent".to_string(),
                 ));
             }
+            if ui.button("New cube window").clicked() {
+                windows_to_create.push(crate::windows::cube_window::CubeWindow::request());
+            }
+            if ui.button("Toggle fullscreen").clicked() {
+                self.fullscreen.toggle(window);
+            }
+            let dark = egui.egui_ctx.style().visuals.dark_mode;
+            if ui.button(if dark { "Light mode" } else { "Dark mode" }).clicked() {
+                egui.egui_ctx.set_visuals(if dark {
+                    egui_multiwin::egui::Visuals::light()
+                } else {
+                    egui_multiwin::egui::Visuals::dark()
+                });
+            }
             if ui.button("Quit").clicked() {
-                quit = true;
+                windows_to_create.push(super::confirm_window::ConfirmWindow::request(self.id));
             }
         });
         egui_multiwin::egui::CentralPanel::default().show(&egui.egui_ctx, |ui| {
@@ -112,10 +128,10 @@ impl TrackedWindow for RootWindow {
                 family: egui_multiwin::egui::FontFamily::Name("computermodern".into()),
             });
             ui.label(t);
-            ui.checkbox(&mut self.summon_groot, "summon groot");
-            if self.summon_groot {
+            ui.checkbox(&mut c.summon_groot, "summon groot");
+            if c.summon_groot {
                 egui.egui_ctx.show_viewport_deferred(
-                    egui_multiwin::egui::viewport::ViewportId::from_hash_of("Testing"),
+                    groot_viewport_id(),
                     egui_multiwin::egui::viewport::ViewportBuilder {
                         ..Default::default()
                     },
@@ -130,6 +146,7 @@ impl TrackedWindow for RootWindow {
         RedrawResponse {
             quit,
             new_windows: windows_to_create,
+            repaint: false,
         }
     }
 }
@@ -34,7 +34,7 @@ pub struct AppCommon {
     clicks: u32,
 }
 
-impl AppCommon {
+impl crate::egui_multiwin_dynamic::multi_window::CommonEventHandler for AppCommon {
     /// Process events, do nothing
     fn process_event(&mut self, _event: egui_multiwin::NoEvent) -> Vec<NewWindowRequest> {
         Vec::new()
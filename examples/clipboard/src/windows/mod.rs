@@ -2,8 +2,7 @@
 
 use egui_multiwin::enum_dispatch::enum_dispatch;
 
-use crate::egui_multiwin_dynamic::tracked_window::{RedrawResponse, TrackedWindow};
-use egui_multiwin::egui_glow::EguiGlow;
+use crate::egui_multiwin_dynamic::tracked_window::{RedrawContext, RedrawResponse, TrackedWindow};
 use std::sync::Arc;
 
 pub mod popup_window;
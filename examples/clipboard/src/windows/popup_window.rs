@@ -1,10 +1,9 @@
 //! This is an example of a popup window. It is likely very crude on the opengl_after function and could probably be optimized
 use crate::egui_multiwin_dynamic::{
     multi_window::NewWindowRequest,
-    tracked_window::{RedrawResponse, TrackedWindow},
+    tracked_window::{RedrawContext, RedrawResponse, TrackedWindow},
 };
 use egui_multiwin::egui_glow::glow;
-use egui_multiwin::egui_glow::EguiGlow;
 
 use crate::AppCommon;
 
@@ -29,10 +28,10 @@ impl PopupWindow {
                 })
                 .with_title(label),
             egui_multiwin::tracked_window::TrackedWindowOptions {
-                vsync: false,
                 shader: None,
+                ..Default::default()
             },
-            egui_multiwin::multi_window::new_id(),
+            egui_multiwin::multi_window::new_id().unwrap(),
         )
     }
 }
@@ -42,6 +41,7 @@ impl TrackedWindow for PopupWindow {
         &mut self,
         _c: &mut AppCommon,
         gl: &std::sync::Arc<egui_multiwin::egui_glow::painter::Context>,
+        _window: &egui_multiwin::winit::window::Window,
     ) {
         use glow::HasContext;
         let shader_version = egui_multiwin::egui_glow::ShaderVersion::get(gl);
@@ -112,13 +112,8 @@ impl TrackedWindow for PopupWindow {
         (c.clicks & 1) == 0
     }
 
-    fn redraw(
-        &mut self,
-        c: &mut AppCommon,
-        egui: &mut EguiGlow,
-        window: &egui_multiwin::winit::window::Window,
-        _clipboard: &mut egui_multiwin::arboard::Clipboard,
-    ) -> RedrawResponse {
+    fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+        let RedrawContext { common: c, egui, window, .. } = ctx;
         let mut quit = false;
 
         egui_multiwin::egui::CentralPanel::default().show(&egui.egui_ctx, |ui| {
@@ -141,6 +136,7 @@ impl TrackedWindow for PopupWindow {
         RedrawResponse {
             quit,
             new_windows: Vec::new(),
+            repaint: false,
         }
     }
 }
@@ -2,12 +2,9 @@
 
 use crate::egui_multiwin_dynamic::{
     multi_window::NewWindowRequest,
-    tracked_window::{RedrawResponse, TrackedWindow},
+    tracked_window::{RedrawContext, RedrawResponse, TrackedWindow},
 };
 use egui_multiwin::egui::FontId;
-use egui_multiwin::egui_glow::EguiGlow;
-
-use crate::AppCommon;
 
 use super::popup_window::PopupWindow;
 
@@ -38,10 +35,10 @@ impl RootWindow {
                 })
                 .with_title("egui-multiwin root window"),
             egui_multiwin::tracked_window::TrackedWindowOptions {
-                vsync: false,
                 shader: None,
+                ..Default::default()
             },
-            egui_multiwin::multi_window::new_id(),
+            egui_multiwin::multi_window::new_id().unwrap(),
         )
     }
 }
@@ -53,13 +50,8 @@ impl TrackedWindow for RootWindow {
 
     fn set_root(&mut self, _root: bool) {}
 
-    fn redraw(
-        &mut self,
-        c: &mut AppCommon,
-        egui: &mut EguiGlow,
-        _window: &egui_multiwin::winit::window::Window,
-        clipboard: &mut egui_multiwin::arboard::Clipboard,
-    ) -> RedrawResponse {
+    fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+        let RedrawContext { common: c, egui, clipboard, .. } = ctx;
         let mut quit = false;
 
         let mut windows_to_create = vec![];
@@ -86,18 +78,19 @@ impl TrackedWindow for RootWindow {
             });
             ui.label(format!("Text from clipboard is {}", self.stuff));
             if ui.button("Click to get clipboard contents").clicked() {
-                if let Ok(s) = clipboard.get_text() {
+                if let Some(s) = clipboard.get_text() {
                     self.stuff = s;
                 }
             }
             if ui.button("Click to put text onto clipboard").clicked() {
-                let _e = clipboard.set_text("This is text from the egui-multiwin demo");
+                clipboard.set_text("This is text from the egui-multiwin demo");
             }
             ui.label(t);
         });
         RedrawResponse {
             quit,
             new_windows: windows_to_create,
+            repaint: false,
         }
     }
 }
@@ -5,6 +5,7 @@
 
 use std::collections::HashSet;
 
+use egui_multiwin::multi_window::WindowTarget;
 use egui_multiwin::winit::{event_loop::EventLoopProxy, window::WindowId};
 
 use egui_multiwin_dynamic::multi_window::{MultiWindow, NewWindowRequest};
@@ -48,8 +49,8 @@ pub struct AppCommon {
 /// The custom event that is passed to windows in the program
 #[derive(Debug)]
 pub struct CustomEvent {
-    /// The target window
-    window: Option<WindowId>,
+    /// The target window, by window id or window request id
+    window: Option<WindowTarget>,
     /// The message
     message: u32,
 }
@@ -57,11 +58,11 @@ pub struct CustomEvent {
 impl CustomEvent {
     /// Get the window id from the event
     fn window_id(&self) -> Option<WindowId> {
-        self.window
+        self.window.and_then(|t| t.window_id())
     }
 }
 
-impl AppCommon {
+impl crate::egui_multiwin_dynamic::multi_window::CommonEventHandler for AppCommon {
     /// Process events
     fn process_event(&mut self, event: CustomEvent) -> Vec<NewWindowRequest> {
         let mut windows = vec![];
@@ -105,8 +106,17 @@ fn main() {
     };
 
     ac.popup_windows.insert(root_window2.id);
-    if let Err(e) = multi_window.add(root_window, &mut ac, &event_loop) {
-        println!("Failed to create main window {:?}", e);
+    match multi_window.add(root_window, &mut ac, &event_loop) {
+        Ok(id) => {
+            // Greet the main window as soon as it exists, using the id returned by add
+            if let Err(e) = ac.sender.send_event(CustomEvent {
+                window: Some(id.into()),
+                message: 40,
+            }) {
+                println!("Failed to send message to main window {:?}", e);
+            }
+        }
+        Err(e) => println!("Failed to create main window {:?}", e),
     }
     if let Err(e) = multi_window.add(root_window2, &mut ac, &event_loop) {
         println!("Failed to create popup window {:?}", e);
@@ -1,10 +1,9 @@
 //! This is an example of a popup window. It is likely very crude on the opengl_after function and could probably be optimized
 use crate::egui_multiwin_dynamic::{
     multi_window::NewWindowRequest,
-    tracked_window::{RedrawResponse, TrackedWindow},
+    tracked_window::{RedrawContext, RedrawResponse, TrackedWindow},
 };
 use egui_multiwin::egui_glow::glow;
-use egui_multiwin::egui_glow::EguiGlow;
 
 use crate::AppCommon;
 use crate::CustomEvent;
@@ -22,7 +21,7 @@ pub struct PopupWindow {
 impl PopupWindow {
     /// Request a new window
     pub fn request(label: String) -> NewWindowRequest {
-        let id = egui_multiwin::multi_window::new_id();
+        let id = egui_multiwin::multi_window::new_id().unwrap();
         NewWindowRequest::new(
             super::MyWindows::Popup(PopupWindow {
                 clicks: 0,
@@ -37,8 +36,9 @@ impl PopupWindow {
                 })
                 .with_title(label),
             egui_multiwin::tracked_window::TrackedWindowOptions {
-                vsync: true,
+                present_mode: Some(egui_multiwin::tracked_window::PresentMode::Fifo),
                 shader: None,
+                ..Default::default()
             },
             id,
         )
@@ -50,6 +50,7 @@ impl TrackedWindow for PopupWindow {
         &mut self,
         _c: &mut AppCommon,
         gl: &std::sync::Arc<egui_multiwin::egui_glow::painter::Context>,
+        _window: &egui_multiwin::winit::window::Window,
     ) {
         use glow::HasContext;
         let shader_version = egui_multiwin::egui_glow::ShaderVersion::get(gl);
@@ -124,14 +125,7 @@ impl TrackedWindow for PopupWindow {
         quit
     }
 
-    fn custom_event(
-        &mut self,
-        event: &CustomEvent,
-        _c: &mut AppCommon,
-        _egui: &mut EguiGlow,
-        _window: &egui_multiwin::winit::window::Window,
-        _clipboard: &mut egui_multiwin::arboard::Clipboard,
-    ) -> RedrawResponse {
+    fn custom_event(&mut self, event: &CustomEvent, _ctx: &mut RedrawContext) -> RedrawResponse {
         println!(
             "Popup window {} received an event {}",
             self.id, event.message
@@ -139,16 +133,12 @@ impl TrackedWindow for PopupWindow {
         RedrawResponse {
             quit: false,
             new_windows: vec![],
+            repaint: false,
         }
     }
 
-    fn redraw(
-        &mut self,
-        c: &mut AppCommon,
-        egui: &mut EguiGlow,
-        window: &egui_multiwin::winit::window::Window,
-        _clipboard: &mut egui_multiwin::arboard::Clipboard,
-    ) -> RedrawResponse {
+    fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+        let RedrawContext { common: c, egui, window, .. } = ctx;
         let mut quit = false;
 
         egui_multiwin::egui::CentralPanel::default().show(&egui.egui_ctx, |ui| {
@@ -173,6 +163,7 @@ impl TrackedWindow for PopupWindow {
         RedrawResponse {
             quit,
             new_windows: Vec::new(),
+            repaint: false,
         }
     }
 }
@@ -2,12 +2,11 @@
 
 use crate::egui_multiwin_dynamic::{
     multi_window::NewWindowRequest,
-    tracked_window::{RedrawResponse, TrackedWindow},
+    tracked_window::{RedrawContext, RedrawResponse, TrackedWindow},
 };
 use egui_multiwin::egui::FontId;
-use egui_multiwin::egui_glow::EguiGlow;
 
-use crate::{AppCommon, CustomEvent};
+use crate::CustomEvent;
 
 use super::popup_window::PopupWindow;
 
@@ -35,10 +34,10 @@ impl RootWindow {
                 })
                 .with_title("egui-multiwin root window"),
             egui_multiwin::tracked_window::TrackedWindowOptions {
-                vsync: false,
                 shader: None,
+                ..Default::default()
             },
-            egui_multiwin::multi_window::new_id(),
+            egui_multiwin::multi_window::new_id().unwrap(),
         )
     }
 }
@@ -48,30 +47,19 @@ impl TrackedWindow for RootWindow {
         true
     }
 
-    fn custom_event(
-        &mut self,
-        event: &CustomEvent,
-        _c: &mut AppCommon,
-        _egui: &mut EguiGlow,
-        _window: &egui_multiwin::winit::window::Window,
-        _clipboard: &mut egui_multiwin::arboard::Clipboard,
-    ) -> RedrawResponse {
+    fn custom_event(&mut self, event: &CustomEvent, _ctx: &mut RedrawContext) -> RedrawResponse {
         println!("Main window received an event {}", event.message);
         RedrawResponse {
             quit: false,
             new_windows: vec![],
+            repaint: false,
         }
     }
 
     fn set_root(&mut self, _root: bool) {}
 
-    fn redraw(
-        &mut self,
-        c: &mut AppCommon,
-        egui: &mut EguiGlow,
-        _window: &egui_multiwin::winit::window::Window,
-        _clipboard: &mut egui_multiwin::arboard::Clipboard,
-    ) -> RedrawResponse {
+    fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+        let RedrawContext { common: c, egui, .. } = ctx;
         let mut quit = false;
 
         let mut windows_to_create = vec![];
@@ -97,42 +85,33 @@ impl TrackedWindow for RootWindow {
             });
             ui.label(t);
 
-            if let Some(wid) = egui_multiwin::multi_window::get_window_id(c.root_window) {
-                ui.label(format!(
-                    "Root window id {} has window id {:?}",
-                    c.root_window, wid
-                ));
-                if ui.button("Send message").clicked() {
-                    if let Err(e) = c.sender.send_event(CustomEvent {
-                        window: Some(wid),
-                        message: 40,
-                    }) {
-                        println!("Failed to send message to root window {:?}", e);
-                    }
+            // Events are addressed by window request id, the window id is looked up when the event is delivered
+            ui.label(format!("Root window id {}", c.root_window));
+            if ui.button("Send message").clicked() {
+                if let Err(e) = c.sender.send_event(CustomEvent {
+                    window: Some(c.root_window.into()),
+                    message: 40,
+                }) {
+                    println!("Failed to send message to root window {:?}", e);
                 }
-            } else {
-                ui.label(format!("Root window id {} failed", c.root_window));
             }
 
             for id in &c.popup_windows {
-                if let Some(wid) = egui_multiwin::multi_window::get_window_id(*id) {
-                    ui.label(format!("Popup window id {} has window id {:?}", id, wid));
-                    if ui.button("Send message").clicked() {
-                        if let Err(e) = c.sender.send_event(CustomEvent {
-                            window: Some(wid),
-                            message: 40,
-                        }) {
-                            println!("Failed to send message to popupwindow {:?}", e);
-                        }
+                ui.label(format!("Popup window id {}", id));
+                if ui.button("Send message").clicked() {
+                    if let Err(e) = c.sender.send_event(CustomEvent {
+                        window: Some((*id).into()),
+                        message: 40,
+                    }) {
+                        println!("Failed to send message to popupwindow {:?}", e);
                     }
-                } else {
-                    ui.label(format!("Popup window id {} failed", id));
                 }
             }
         });
         RedrawResponse {
             quit,
             new_windows: windows_to_create,
+            repaint: false,
         }
     }
 }
@@ -2,12 +2,11 @@
 
 use crate::egui_multiwin_dynamic::{
     multi_window::NewWindowRequest,
-    tracked_window::{RedrawResponse, TrackedWindow},
+    tracked_window::{RedrawContext, RedrawResponse, TrackedWindow},
 };
 use egui_multiwin::egui::FontId;
-use egui_multiwin::egui_glow::EguiGlow;
 
-use crate::{AppCommon, CustomEvent};
+use crate::CustomEvent;
 
 use super::popup_window::PopupWindow;
 
@@ -35,10 +34,10 @@ impl RootWindow {
                 })
                 .with_title("egui-multiwin root window"),
             egui_multiwin::tracked_window::TrackedWindowOptions {
-                vsync: false,
                 shader: None,
+                ..Default::default()
             },
-            egui_multiwin::multi_window::new_id(),
+            egui_multiwin::multi_window::new_id().unwrap(),
         )
     }
 }
@@ -48,30 +47,19 @@ impl TrackedWindow for RootWindow {
         true
     }
 
-    fn custom_event(
-        &mut self,
-        event: &CustomEvent,
-        _c: &mut AppCommon,
-        _egui: &mut EguiGlow,
-        _window: &egui_multiwin::winit::window::Window,
-        _clipboard: &mut egui_multiwin::arboard::Clipboard,
-    ) -> RedrawResponse {
+    fn custom_event(&mut self, event: &CustomEvent, _ctx: &mut RedrawContext) -> RedrawResponse {
         println!("Main window received an event {}", event.message);
         RedrawResponse {
             quit: false,
             new_windows: vec![],
+            repaint: false,
         }
     }
 
     fn set_root(&mut self, _root: bool) {}
 
-    fn redraw(
-        &mut self,
-        c: &mut AppCommon,
-        egui: &mut EguiGlow,
-        _window: &egui_multiwin::winit::window::Window,
-        _clipboard: &mut egui_multiwin::arboard::Clipboard,
-    ) -> RedrawResponse {
+    fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+        let RedrawContext { common: c, egui, .. } = ctx;
         let mut quit = false;
 
         let mut windows_to_create = vec![];
@@ -118,12 +106,14 @@ impl TrackedWindow for RootWindow {
                 if let Some(wid) = egui_multiwin::multi_window::get_window_id(*id) {
                     ui.label(format!("Popup window id {} has window id {:?}", id, wid));
                     if ui.button("Send message").clicked() {
-                        if let Err(e) = c.sender.send_event(CustomEvent {
-                            window: Some(wid),
-                            message: 40,
-                        }) {
-                            println!("Failed to send message to popupwindow {:?}", e);
-                        }
+                        // The mailbox targets the window by request id, no proxy or window id needed
+                        crate::egui_multiwin_dynamic::multi_window::post_to_window(
+                            *id,
+                            CustomEvent {
+                                window: None,
+                                message: 40,
+                            },
+                        );
                     }
                 } else {
                     ui.label(format!("Popup window id {} failed", id));
@@ -133,6 +123,7 @@ impl TrackedWindow for RootWindow {
         RedrawResponse {
             quit,
             new_windows: windows_to_create,
+            repaint: false,
         }
     }
 }
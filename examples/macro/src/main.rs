@@ -59,7 +59,7 @@ impl CustomEvent {
     }
 }
 
-impl AppCommon {
+impl crate::egui_multiwin_dynamic::multi_window::CommonEventHandler for AppCommon {
     /// Process events
     fn process_event(
         &mut self,
@@ -98,8 +98,17 @@ fn main() {
             };
 
             ac.popup_windows.insert(root_window2.id);
-            if let Err(e) = multi_window.add(root_window, &mut ac, event_loop) {
-                println!("Failed to create main window {:?}", e);
+            match multi_window.add(root_window, &mut ac, event_loop) {
+                Ok(id) => {
+                    // Greet the main window as soon as it exists, using the id returned by add
+                    if let Err(e) = ac.sender.send_event(CustomEvent {
+                        window: Some(id),
+                        message: 40,
+                    }) {
+                        println!("Failed to send message to main window {:?}", e);
+                    }
+                }
+                Err(e) => println!("Failed to create main window {:?}", e),
             }
             if let Err(e) = multi_window.add(root_window2, &mut ac, event_loop) {
                 println!("Failed to create popup window {:?}", e);
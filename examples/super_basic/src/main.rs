@@ -3,26 +3,20 @@
 
 //! Shows a very simple example with minimal code
 
-/// Macro generated code
-pub mod egui_multiwin_dynamic {
-    egui_multiwin::tracked_window!(crate::AppCommon, crate::CustomEvent, crate::MyWindows);
-    egui_multiwin::multi_window!(crate::AppCommon, crate::CustomEvent, crate::MyWindows);
-}
-
-/// The windows for the program
-#[enum_dispatch(TrackedWindow)]
-pub enum MyWindows {
-    /// A popup window
-    Popup(PopupWindow),
+egui_multiwin::window_enum! {
+    common: crate::AppCommon,
+    event: crate::CustomEvent,
+    /// The windows for the program
+    pub enum MyWindows {
+        /// A popup window
+        Popup(PopupWindow),
+    }
 }
 
-use egui_multiwin::arboard;
-use egui_multiwin::egui_glow::EguiGlow;
-use egui_multiwin::enum_dispatch::enum_dispatch;
 use egui_multiwin_dynamic::multi_window::NewWindowRequest;
+use egui_multiwin_dynamic::tracked_window::RedrawContext;
 use egui_multiwin_dynamic::tracked_window::RedrawResponse;
 use egui_multiwin_dynamic::tracked_window::TrackedWindow;
-use std::sync::Arc;
 
 /// Data common to all windows
 pub struct AppCommon {
@@ -62,10 +56,10 @@ impl PopupWindow {
                 })
                 .with_title("A window"),
             egui_multiwin::tracked_window::TrackedWindowOptions {
-                vsync: false,
                 shader: None,
+                ..Default::default()
             },
-            egui_multiwin::multi_window::new_id(),
+            egui_multiwin::multi_window::new_id().unwrap(),
         )
     }
 }
@@ -75,13 +69,8 @@ impl TrackedWindow for PopupWindow {
         true
     }
 
-    fn redraw(
-        &mut self,
-        c: &mut AppCommon,
-        egui: &mut EguiGlow,
-        _window: &egui_multiwin::winit::window::Window,
-        _clipboard: &mut arboard::Clipboard,
-    ) -> RedrawResponse {
+    fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+        let RedrawContext { common: c, egui, .. } = ctx;
         let quit = false;
         egui_multiwin::egui::CentralPanel::default().show(&egui.egui_ctx, |ui| {
             ui.heading(format!("number {}", c.clicks));
@@ -89,11 +78,12 @@ impl TrackedWindow for PopupWindow {
         RedrawResponse {
             quit,
             new_windows: Vec::new(),
+            repaint: false,
         }
     }
 }
 
-impl AppCommon {
+impl crate::egui_multiwin_dynamic::multi_window::CommonEventHandler for AppCommon {
     /// Process events
     fn process_event(&mut self, event: CustomEvent) -> Vec<NewWindowRequest> {
         let mut windows_to_create = vec![];
@@ -1,12 +1,9 @@
 //! Code for the root window
 use crate::egui_multiwin_dynamic::{
     multi_window::NewWindowRequest,
-    tracked_window::{RedrawResponse, TrackedWindow},
+    tracked_window::{RedrawContext, RedrawResponse, TrackedWindow},
 };
 use egui_multiwin::egui::FontId;
-use egui_multiwin::egui_glow::EguiGlow;
-
-use crate::AppCommon;
 
 use super::popup_window::PopupWindow;
 
@@ -16,8 +13,6 @@ pub struct RootWindow {
     pub button_press_count: u32,
     /// The number of popups created
     pub num_popups_created: u32,
-    /// The last time the window was updated
-    prev_time: std::time::Instant,
     /// The calculated frames per second of the window
     fps: Option<f32>,
 }
@@ -29,7 +24,6 @@ impl RootWindow {
             super::MyWindows::Root(RootWindow {
                 button_press_count: 0,
                 num_popups_created: 0,
-                prev_time: std::time::Instant::now(),
                 fps: None,
             }),
             egui_multiwin::winit::window::WindowBuilder::new()
@@ -40,10 +34,11 @@ impl RootWindow {
                 })
                 .with_title("egui-multiwin root window"),
             egui_multiwin::tracked_window::TrackedWindowOptions {
-                vsync: true,
+                present_mode: Some(egui_multiwin::tracked_window::PresentMode::Fifo),
                 shader: None,
+                ..Default::default()
             },
-            egui_multiwin::multi_window::new_id(),
+            egui_multiwin::multi_window::new_id().unwrap(),
         )
     }
 }
@@ -55,22 +50,13 @@ impl TrackedWindow for RootWindow {
 
     fn set_root(&mut self, _root: bool) {}
 
-    fn redraw(
-        &mut self,
-        c: &mut AppCommon,
-        egui: &mut EguiGlow,
-        _window: &egui_multiwin::winit::window::Window,
-        _clipboard: &mut egui_multiwin::arboard::Clipboard,
-    ) -> RedrawResponse {
+    fn redraw(&mut self, ctx: &mut RedrawContext) -> RedrawResponse {
+        let RedrawContext { common: c, egui, dt, .. } = ctx;
         let mut quit = false;
 
         egui.egui_ctx.request_repaint();
 
-        let cur_time = std::time::Instant::now();
-        let delta = cur_time.duration_since(self.prev_time);
-        self.prev_time = cur_time;
-
-        let new_fps = 1_000_000_000.0 / delta.as_nanos() as f32;
+        let new_fps = 1_000_000_000.0 / dt.as_nanos() as f32;
         if let Some(fps) = &mut self.fps {
             *fps = (*fps * 0.95) + (0.05 * new_fps);
         } else {
@@ -110,6 +96,7 @@ impl TrackedWindow for RootWindow {
         RedrawResponse {
             quit,
             new_windows: windows_to_create,
+            repaint: false,
         }
     }
 }